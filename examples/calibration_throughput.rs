@@ -0,0 +1,52 @@
+//! Day 1 calibration throughput on a generated input, line-based `calibrate` against the
+//! byte-level `CalibrationStream`.
+//!
+//!     cargo run --release --example calibration_throughput -- [megabytes]
+
+use std::env;
+use std::time::Instant;
+
+use adventofcode2023::day_1::{self, calibrate_reader, MissingDigits, DIGIT_WORDS};
+use adventofcode2023::rng::XorShift;
+use adventofcode2023::runner::Part;
+
+/// Lines of letters, digits and digit words from a fixed xorshift seed, so runs are comparable.
+fn generate(bytes: usize) -> String {
+    let mut rng = XorShift::new(0x2023_1201);
+    let mut out = String::with_capacity(bytes + 64);
+    while out.len() < bytes {
+        let tokens = 3 + rng.below(8);
+        for _ in 0..tokens {
+            match rng.below(4) {
+                0 => out.push(char::from(b'0' + rng.below(10) as u8)),
+                1 => out.push_str(DIGIT_WORDS[rng.below(10) as usize]),
+                _ => out.push(char::from(b'a' + rng.below(26) as u8)),
+            }
+        }
+        out.push(char::from(b'0' + rng.below(10) as u8));
+        out.push('\n');
+    }
+    out
+}
+
+fn main() {
+    let megabytes: usize = env::args().nth(1).and_then(|a| a.parse().ok()).unwrap_or(64);
+    let input = generate(megabytes * 1024 * 1024);
+    let calibrator = day_1::calibrator(Part::B);
+    let mb = input.len() as f64 / (1024.0 * 1024.0);
+
+    let start = Instant::now();
+    let lines: Vec<String> = input.lines().map(str::to_string).collect();
+    let by_line = day_1::calibrate(&lines, &calibrator, MissingDigits::Error).unwrap();
+    let by_line_total = by_line.total();
+    let by_line_time = start.elapsed();
+
+    let start = Instant::now();
+    let streamed = calibrate_reader(&calibrator, input.as_bytes(), MissingDigits::Error).unwrap();
+    let streamed_time = start.elapsed();
+
+    assert_eq!(by_line_total, streamed.total);
+    println!("{mb:.1} MiB, {} lines, sum {}", streamed.lines, streamed.total);
+    println!("line-based: {:>8.1?} {:>8.1} MiB/s", by_line_time, mb / by_line_time.as_secs_f64());
+    println!("streaming:  {:>8.1?} {:>8.1} MiB/s", streamed_time, mb / streamed_time.as_secs_f64());
+}
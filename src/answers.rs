@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use nom::branch::alt;
+use nom::bytes::complete::{is_not, tag};
+use nom::character::complete::{alphanumeric1, char, digit1, space0};
+use nom::combinator::{all_consuming, map, map_res, opt};
+use nom::sequence::{delimited, preceded, separated_pair, terminated, tuple};
+
+use crate::error::{parse_line, Error, IResult, Result};
+use crate::input::{data_dir, read_file};
+use crate::runner::Part;
+
+/// Known-good answers for our own inputs, keyed by day and part.
+///
+/// The file is a small subset of TOML, one table per day:
+///
+/// ```toml
+/// [day_1]
+/// a = "54239"
+/// b = 55343
+/// ```
+#[derive(Debug, Default)]
+pub struct Answers {
+    recorded: HashMap<(u32, Part), String>,
+}
+
+pub fn default_path() -> PathBuf {
+    data_dir().join("answers.toml")
+}
+
+enum Line<'a> {
+    Blank,
+    Day(u32),
+    Answer(&'a str, &'a str),
+}
+
+fn parse_comment(input: &str) -> IResult<'_, ()> {
+    map(opt(preceded(char('#'), opt(is_not("\n")))), |_| ())(input)
+}
+
+fn parse_day(input: &str) -> IResult<'_, Line<'_>> {
+    map(
+        delimited(tag("[day_"), map_res(digit1, str::parse), char(']')),
+        Line::Day,
+    )(input)
+}
+
+fn parse_value(input: &str) -> IResult<'_, &str> {
+    alt((delimited(char('"'), is_not("\""), char('"')), alphanumeric1))(input)
+}
+
+fn parse_answer(input: &str) -> IResult<'_, Line<'_>> {
+    map(
+        separated_pair(alphanumeric1, tuple((space0, char('='), space0)), parse_value),
+        |(part, value)| Line::Answer(part, value),
+    )(input)
+}
+
+fn parse_answers_line(input: &str) -> IResult<'_, Line<'_>> {
+    all_consuming(delimited(
+        space0,
+        alt((parse_day, parse_answer, map(tag(""), |_| Line::Blank))),
+        terminated(space0, parse_comment),
+    ))(input)
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Answers> {
+        let mut answers = Answers::default();
+        let mut day = None;
+        for (i, line) in input.lines().enumerate() {
+            match parse_line(i, line, parse_answers_line)? {
+                Line::Blank => {}
+                Line::Day(d) => day = Some(d),
+                Line::Answer(part, value) => {
+                    let at = |e: Error| e.at_line(i + 1);
+                    let day = day.ok_or_else(|| at(Error::invalid("answer outside of a [day_N] table")))?;
+                    let part = Part::parse(part).ok_or_else(|| at(Error::invalid(format!("unknown part `{part}`"))))?;
+                    answers.recorded.insert((day, part), value.to_string());
+                }
+            }
+        }
+        Ok(answers)
+    }
+
+    pub fn load(path: &Path) -> Result<Answers> {
+        Answers::parse(&read_file(path)?)
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<&str> {
+        self.recorded.get(&(day, part)).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_quoted_and_bare_values_and_comments() {
+        let answers = Answers::parse("# our answers
+[day_1]
+a = \"54239\"  # quoted
+b=55343
+
+  [day_4]   # indented table
+a = 13
+").unwrap();
+        assert_eq!(answers.get(1, Part::A), Some("54239"));
+        assert_eq!(answers.get(1, Part::B), Some("55343"));
+        assert_eq!(answers.get(4, Part::A), Some("13"));
+        assert_eq!(answers.get(4, Part::B), None);
+        assert_eq!(answers.get(2, Part::A), None);
+    }
+
+    #[test]
+    fn rejects_answers_outside_a_day_and_unknown_parts() {
+        let e = Answers::parse("# no table yet\na = \"1\"").unwrap_err();
+        assert_eq!(e.to_string(), "line 2: answer outside of a [day_N] table");
+
+        let e = Answers::parse("[day_1]\nc = 3").unwrap_err();
+        assert_eq!(e.to_string(), "line 2: unknown part `c`");
+
+        let e = Answers::parse("[day_1]\na = \"1").unwrap_err();
+        assert_eq!(e.line, Some(2));
+    }
+}
@@ -0,0 +1,112 @@
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+
+/// Aho–Corasick automaton over bytes: finds every occurrence of every pattern, overlapping
+/// ones included, in a single forward pass.
+///
+/// The failure links are folded into a dense transition table, so each input byte costs one
+/// lookup and [`Automaton::step`] never allocates. Patterns are matched byte for byte, UTF-8
+/// ones included: a match can never start inside a multi-byte character.
+#[derive(Clone, Debug)]
+pub struct Automaton<V> {
+    /// `state * 256 + byte` -> next state.
+    delta: Vec<u32>,
+    /// Patterns ending at each state, as `(pattern length, value)`, longest first.
+    outputs: Vec<Vec<(usize, V)>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Match<V> {
+    /// Byte offset of the first byte.
+    pub start: usize,
+    /// Byte offset one past the last byte.
+    pub end: usize,
+    pub value: V,
+}
+
+impl<V: Copy> Automaton<V> {
+    pub const START: u32 = 0;
+
+    pub fn new<P: AsRef<[u8]>>(patterns: impl IntoIterator<Item = (P, V)>) -> Automaton<V> {
+        // trie first, with 0 standing for "no edge" since the root is never a child
+        let mut delta = vec![0u32; 256];
+        let mut outputs: Vec<Vec<(usize, V)>> = vec![vec![]];
+        for (pattern, value) in patterns {
+            let pattern = pattern.as_ref();
+            if pattern.is_empty() {
+                continue;
+            }
+            let mut state = 0;
+            for &b in pattern {
+                let next = delta[state * 256 + b as usize] as usize;
+                state = if next == 0 {
+                    let new = outputs.len();
+                    delta[state * 256 + b as usize] = new as u32;
+                    delta.extend([0; 256]);
+                    outputs.push(vec![]);
+                    new
+                } else {
+                    next
+                };
+            }
+            outputs[state].push((pattern.len(), value));
+        }
+
+        // breadth first so a state's failure target is complete before the state itself
+        let mut fail = vec![0usize; outputs.len()];
+        let mut queue: VecDeque<usize> = (0..256).map(|b| delta[b] as usize).filter(|s| *s != 0).collect();
+        while let Some(state) = queue.pop_front() {
+            let inherited = outputs[fail[state]].clone();
+            outputs[state].extend(inherited);
+            outputs[state].sort_by_key(|o| Reverse(o.0));
+            for b in 0..256 {
+                let child = delta[state * 256 + b] as usize;
+                let fallback = delta[fail[state] * 256 + b];
+                if child == 0 {
+                    delta[state * 256 + b] = fallback;
+                } else {
+                    fail[child] = fallback as usize;
+                    queue.push_back(child);
+                }
+            }
+        }
+        Automaton { delta, outputs }
+    }
+
+    pub fn step(&self, state: u32, byte: u8) -> u32 {
+        self.delta[state as usize * 256 + byte as usize]
+    }
+
+    /// Patterns ending at `state`, as `(pattern length, value)`, longest first.
+    pub fn outputs(&self, state: u32) -> &[(usize, V)] {
+        &self.outputs[state as usize]
+    }
+
+    /// Every match in `haystack`, ordered by end offset, longer first on a tie.
+    pub fn find_iter<'a>(&'a self, haystack: &'a [u8]) -> impl Iterator<Item = Match<V>> + 'a {
+        let mut state = Self::START;
+        haystack.iter().enumerate().flat_map(move |(i, &b)| {
+            state = self.step(state, b);
+            self.outputs(state).iter().map(move |&(len, value)| Match { start: i + 1 - len, end: i + 1, value })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_overlapping_matches() {
+        let automaton = Automaton::new([("eight", 8), ("two", 2), ("one", 1)]);
+        let found: Vec<_> = automaton.find_iter(b"xeightwone").map(|m| (m.start, m.value)).collect();
+        assert_eq!(found, vec![(1, 8), (5, 2), (7, 1)]);
+    }
+
+    #[test]
+    fn reports_patterns_nested_in_others() {
+        let automaton = Automaton::new([("abcd", 1), ("bc", 2), ("c", 3)]);
+        let found: Vec<_> = automaton.find_iter(b"abcd").map(|m| (m.start, m.end, m.value)).collect();
+        assert_eq!(found, vec![(1, 3, 2), (2, 3, 3), (0, 4, 1)]);
+    }
+}
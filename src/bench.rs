@@ -0,0 +1,125 @@
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::output::json_string;
+use crate::runner::Part;
+use crate::solution::Solution;
+
+/// Wall time spread over repeated runs of one phase.
+#[derive(Clone, Copy, Debug)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>) -> Stats {
+        samples.sort();
+        Stats {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+pub struct Timings {
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// Times `runs` parses of `input` and `runs` solves of `part`, each solve against a fresh model
+/// so a solver cannot benefit from work cached by the previous run.
+pub fn time<S: Solution>(input: &str, part: Part, runs: usize) -> Result<Timings> {
+    if runs == 0 {
+        return Err(Error::invalid("bench needs at least one run"));
+    }
+    let mut parse = Vec::with_capacity(runs);
+    let mut solve = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let start = Instant::now();
+        let model = S::parse(input)?;
+        parse.push(start.elapsed());
+
+        let start = Instant::now();
+        match part {
+            Part::A => S::part_a(&model)?,
+            Part::B => S::part_b(&model)?,
+        };
+        solve.push(start.elapsed());
+    }
+    Ok(Timings { parse: Stats::from_samples(parse), solve: Stats::from_samples(solve) })
+}
+
+/// One benchmarked part, or why it could not be timed.
+pub struct Record {
+    pub day: u32,
+    pub part: Part,
+    pub runs: usize,
+    pub timings: Result<Timings>,
+}
+
+fn stats_json(stats: &Stats) -> String {
+    format!(
+        r#"{{"min":{},"median":{},"max":{}}}"#,
+        stats.min.as_micros(), stats.median.as_micros(), stats.max.as_micros()
+    )
+}
+
+/// All records as one JSON array, times in microseconds, so runs from two commits can be diffed.
+/// A failed part has null times and an `"error"` message instead.
+pub fn to_json(records: &[Record]) -> String {
+    let rows: Vec<String> = records.iter().map(|r| {
+        let outcome = match &r.timings {
+            Ok(t) => format!(r#""parse_us":{},"solve_us":{}"#, stats_json(&t.parse), stats_json(&t.solve)),
+            Err(e) => format!(r#""parse_us":null,"solve_us":null,"error":{}"#, json_string(&e.to_string())),
+        };
+        format!(r#"  {{"day":{},"part":"{}","runs":{},{outcome}}}"#, r.day, r.part, r.runs)
+    }).collect();
+    format!("[\n{}\n]", rows.join(",\n"))
+}
+
+pub fn to_table(records: &[Record]) -> String {
+    let mut out = format!(
+        "{:<4} {:<4} {:>12} {:>12} {:>12} {:>12} {:>12} {:>12}\n",
+        "day", "part", "parse min", "parse med", "parse max", "solve min", "solve med", "solve max"
+    );
+    for r in records {
+        match &r.timings {
+            Ok(Timings { parse: p, solve: s }) => out.push_str(&format!(
+                "{:<4} {:<4} {:>12?} {:>12?} {:>12?} {:>12?} {:>12?} {:>12?}\n",
+                r.day, r.part, p.min, p.median, p.max, s.min, s.median, s.max
+            )),
+            Err(e) => out.push_str(&format!("{:<4} {:<4} FAILED ({e})\n", r.day, r.part)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_pick_min_median_max() {
+        let ms = |v: u64| Duration::from_millis(v);
+        let stats = Stats::from_samples(vec![ms(5), ms(1), ms(9), ms(3), ms(7)]);
+        assert_eq!((stats.min, stats.median, stats.max), (ms(1), ms(5), ms(9)));
+    }
+
+    #[test]
+    fn json_has_one_object_per_record() {
+        let us = |v: u64| Duration::from_micros(v);
+        let stats = Stats { min: us(1), median: us(2), max: us(3) };
+        let records = [
+            Record { day: 5, part: Part::B, runs: 3, timings: Ok(Timings { parse: stats, solve: stats }) },
+            Record { day: 6, part: Part::A, runs: 3, timings: Err(Error::invalid("solver panicked").in_day(6)) },
+        ];
+        assert_eq!(
+            to_json(&records),
+            "[\n  {\"day\":5,\"part\":\"b\",\"runs\":3,\"parse_us\":{\"min\":1,\"median\":2,\"max\":3},\"solve_us\":{\"min\":1,\"median\":2,\"max\":3}},\n  {\"day\":6,\"part\":\"a\",\"runs\":3,\"parse_us\":null,\"solve_us\":null,\"error\":\"day_6: solver panicked\"}\n]"
+        );
+        assert!(to_table(&records).ends_with("6    a    FAILED (day_6: solver panicked)\n"));
+    }
+}
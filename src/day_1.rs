@@ -1,133 +1,463 @@
+use std::io::{self, Read};
+use std::path::Path;
 
-use std::fs::File;
-use std::io::{BufReader, BufRead};
-use std::iter::Iterator;
-use std::string::ToString;
+use nom::bytes::complete::is_not;
+use nom::character::complete::{char, digit1, space0};
+use nom::combinator::{all_consuming, map_res, opt};
+use nom::sequence::{delimited, preceded, separated_pair, terminated, tuple};
 
-pub fn day_1a() {
-    let file = File::open("data/day_1").unwrap();
-    let reader = BufReader::new(file);
+use crate::automaton::Match;
+use crate::error::{parse_line, Error, IResult, Result};
+use crate::input::read_file;
+use crate::runner::Part;
+use crate::scanner::{Scan, Scanner};
+use crate::solution::{Answer, Solution};
 
-    let mut s = 0;
-    for line in reader.lines() {
-        let a = line.unwrap();
+pub struct Day1;
 
-        let mut c_val = 0;
+impl Solution for Day1 {
+    type Model = Vec<String>;
 
-        let first_v = a.chars().find(|c|c.is_numeric()).unwrap().to_digit(10).unwrap();
-        let last_v = a.chars().rev().find(|c|c.is_numeric()).unwrap().to_digit(10).unwrap();
-        c_val += first_v * 10;
-        c_val += last_v;
-        s += c_val;
+    fn parse(input: &str) -> Result<Self::Model> {
+        Ok(input.lines().map(str::to_string).collect())
+    }
+
+    fn part_a(lines: &Self::Model) -> Result<Answer> {
+        Ok(day_1a(lines, MissingDigits::default())?.into())
+    }
+
+    fn part_b(lines: &Self::Model) -> Result<Answer> {
+        Ok(day_1b(lines, MissingDigits::default())?.into())
     }
-    println!("{}", s)
 }
 
 pub static DIGIT_WORDS: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
-pub struct MatchingWord {
-    buffer: String
+
+/// Words counted as digits on top of the literal `0`-`9`, each with the value it stands for.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Vocabulary {
+    words: Vec<(String, u32)>,
+}
+
+fn parse_vocabulary_entry(input: &str) -> IResult<'_, (&str, u32)> {
+    let comment = opt(preceded(char('#'), opt(is_not("\n"))));
+    let entry = separated_pair(is_not(" \t=#"), tuple((space0, char('='), space0)), map_res(digit1, str::parse));
+    all_consuming(delimited(space0, entry, terminated(space0, comment)))(input)
 }
 
-impl Default for MatchingWord {
-    fn default() -> Self {
-        MatchingWord{buffer: "".to_string()}
+impl Vocabulary {
+    /// The spelled-out English digits, what the puzzle uses.
+    pub fn english() -> Vocabulary {
+        DIGIT_WORDS.iter().zip(0..).collect()
+    }
+
+    /// Adds `word`, replacing its previous value if it was already there.
+    pub fn insert(&mut self, word: &str, value: u32) {
+        match self.words.iter_mut().find(|(w, _)| w == word) {
+            Some(entry) => entry.1 = value,
+            None => self.words.push((word.to_string(), value)),
+        }
+    }
+
+    /// One `word = value` per line, `#` starting a comment.
+    pub fn parse(input: &str) -> Result<Vocabulary> {
+        let mut vocabulary = Vocabulary::default();
+        for (i, line) in input.lines().enumerate() {
+            let content = line.trim_start();
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+            let (word, value) = parse_line(i, line, parse_vocabulary_entry)?;
+            vocabulary.insert(word, value);
+        }
+        Ok(vocabulary)
+    }
+
+    pub fn load(path: &Path) -> Result<Vocabulary> {
+        Vocabulary::parse(&read_file(path)?)
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.words.iter().map(|(w, v)| (w.as_str(), *v))
     }
 }
 
-fn find_digit(a: &str, reverse: bool) -> u32 {
-    let mut v = 0;
-    let mut current_state = MatchingWord::default();
-    let chars: Vec<char> = if reverse { a.chars().rev().collect() } else { a.chars().collect() };
-    for c in chars {
-        if c.is_numeric() {
-            v = c.to_digit(10).unwrap();
-            current_state = MatchingWord::default();
-            break;
+impl<W: AsRef<str>> FromIterator<(W, u32)> for Vocabulary {
+    fn from_iter<I: IntoIterator<Item = (W, u32)>>(iter: I) -> Self {
+        let mut vocabulary = Vocabulary::default();
+        for (word, value) in iter {
+            vocabulary.insert(word.as_ref(), value);
         }
+        vocabulary
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    Digit,
+    Word,
+}
 
-        current_state.buffer.push(c);
+/// One matched digit, `start..end` being its byte range in the line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub start: usize,
+    pub end: usize,
+    pub kind: TokenKind,
+    pub value: u32,
+}
+
+/// How a line's calibration value came about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineReport {
+    pub first: Token,
+    pub last: Token,
+    pub value: u64,
+}
 
-        let mut full_match = false;
+/// `first * 10 + last`: the two-digit number for digits, and no overflow for the larger
+/// values a vocabulary may give its words, such as 20 for `twenty`.
+fn combine(first: u32, last: u32) -> u64 {
+    first as u64 * 10 + last as u64
+}
 
-        /*
-        here is where I got wrong
-        When going through word check, there are 3 cases:
-        1. found a full match, break and return
-        2. found at least 1 partial match, continue collect buffer
-        3. no partial match, here we should enter a loop of popping char off 1 by 1 from
-        */
-        loop {
-            if current_state.buffer.is_empty() {
-                break
+/// Extracts calibration values: the first and last digit of a line, literal or from a [`Vocabulary`].
+pub struct Calibrator {
+    scanner: Scanner<(u32, TokenKind)>,
+}
+
+impl Calibrator {
+    pub fn new(vocabulary: &Vocabulary) -> Calibrator {
+        let digits = (0..10).map(|d| (d.to_string(), (d, TokenKind::Digit)));
+        let words = vocabulary.words().map(|(w, v)| (w.to_string(), (v, TokenKind::Word)));
+        Calibrator { scanner: Scanner::new(digits.chain(words)) }
+    }
+
+    /// Literal digits only.
+    pub fn digits() -> Calibrator {
+        Calibrator::new(&Vocabulary::default())
+    }
+
+    /// The first and last token of `line`, `None` when there is none.
+    ///
+    /// Every match is seen, overlaps included, so in "eightwo" the last digit is the two.
+    pub fn report(&self, line: &str) -> Option<LineReport> {
+        let (first, last) = self.scanner.first_last(line)?;
+        let token = |m: Match<(u32, TokenKind)>| Token { start: m.start, end: m.end, kind: m.value.1, value: m.value.0 };
+        let (first, last) = (token(first), token(last));
+        Some(LineReport { first, last, value: combine(first.value, last.value) })
+    }
+
+    /// The [`combine`]d first and last digit, `None` when there is no digit.
+    pub fn value(&self, line: &str) -> Option<u64> {
+        self.report(line).map(|r| r.value)
+    }
+
+    /// Every line of `input` with its first and last token underlined and the value they make.
+    pub fn explain(&self, input: &str) -> String {
+        let mut out = String::new();
+        for (i, line) in input.lines().enumerate() {
+            out.push_str(&format!("{:>5} | {line}\n", i + 1));
+            let Some(report) = self.report(line) else {
+                out.push_str("      | no digit\n");
+                continue;
+            };
+            // carets go under characters, not bytes
+            let column = |byte: usize| line[..byte].chars().count();
+            let mut marks = vec![' '; line.chars().count()];
+            for token in [report.first, report.last] {
+                marks[column(token.start)..column(token.end)].fill('^');
             }
-            let mut partial_match = false;
-            for (idx, word) in DIGIT_WORDS.iter().enumerate() {
-                let le_word: String = if reverse { word.chars().rev().collect() } else { word.chars().collect() };
-
-                let cur = &current_state.buffer;
-
-                full_match = *cur == le_word;
-                if full_match {
-                    v = idx as u32;
-                    break;
-                } else if le_word.starts_with(cur) {
-                    partial_match = true;
-                    break;
+            let describe = |t: Token| format!("{} ({:?} at byte {})", t.value, t.kind, t.start).to_lowercase();
+            out.push_str(&format!(
+                "      | {} {} .. {} = {}\n",
+                marks.iter().collect::<String>(), describe(report.first), describe(report.last), report.value
+            ));
+        }
+        out
+    }
+}
+
+/// What each part counts as a digit: literal ones for part a, English words as well for part b.
+pub fn calibrator(part: Part) -> Calibrator {
+    match part {
+        Part::A => Calibrator::digits(),
+        Part::B => Calibrator::new(&Vocabulary::english()),
+    }
+}
+
+/// What to do with a line that holds no digit at all.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissingDigits {
+    /// Fail, naming such lines, the first ten when there are more.
+    #[default]
+    Error,
+    /// Leave the line out of the values.
+    Skip,
+    /// Count the line as 0.
+    Zero,
+}
+
+/// Per-line calibration values, `(line number, value)` with 1-based line numbers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Calibration {
+    pub values: Vec<(usize, u64)>,
+    /// Lines without a digit, whatever the policy did with them.
+    pub missing: Vec<usize>,
+}
+
+impl Calibration {
+    pub fn total(&self) -> u64 {
+        self.values.iter().map(|(_, v)| v).sum()
+    }
+}
+
+pub fn calibrate(lines: &[String], calibrator: &Calibrator, policy: MissingDigits) -> Result<Calibration> {
+    let mut calibration = Calibration::default();
+    for (i, line) in lines.iter().enumerate() {
+        match calibrator.value(line) {
+            Some(v) => calibration.values.push((i + 1, v)),
+            None => {
+                calibration.missing.push(i + 1);
+                if policy == MissingDigits::Zero {
+                    calibration.values.push((i + 1, 0));
                 }
             }
-            if full_match || partial_match {
-                break
+        }
+    }
+    match policy {
+        MissingDigits::Error if !calibration.missing.is_empty() => Err(missing_digits(&calibration.missing)),
+        _ => Ok(calibration),
+    }
+}
+
+/// The [`MissingDigits::Error`] failure for every extractor: one line gets its own location,
+/// several are listed, the first ten of them, so a huge generated input still gives a short message.
+fn missing_digits(lines: &[usize]) -> Error {
+    const LISTED: usize = 10;
+    match lines {
+        [line] => Error::invalid("no digit in line").at_line(*line),
+        _ => {
+            let listed: Vec<String> = lines.iter().take(LISTED).map(usize::to_string).collect();
+            let more = match lines.len().saturating_sub(LISTED) {
+                0 => String::new(),
+                n => format!(" and {n} more"),
+            };
+            Error::invalid(format!("no digit in lines {}{more}", listed.join(", ")))
+        }
+    }
+}
+
+/// Totals from a [`CalibrationStream`]. The sum is 64 bit, generated inputs can be far
+/// larger than the puzzle's.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StreamTotals {
+    pub total: u64,
+    pub lines: usize,
+    /// 1-based numbers of the lines without a digit, empty for a clean input.
+    pub missing: Vec<usize>,
+}
+
+/// Line-by-line calibration over raw bytes fed in chunks of any size, for inputs too large to
+/// hold as `String`s.
+///
+/// Feeding does not allocate: the scan of the current line carries over from one chunk to the
+/// next. Only lines without a digit are remembered.
+pub struct CalibrationStream<'a> {
+    line: Scan<'a, (u32, TokenKind)>,
+    totals: StreamTotals,
+}
+
+impl<'a> CalibrationStream<'a> {
+    pub fn new(calibrator: &'a Calibrator) -> CalibrationStream<'a> {
+        CalibrationStream { line: calibrator.scanner.start(), totals: StreamTotals::default() }
+    }
+
+    pub fn feed(&mut self, chunk: &[u8]) {
+        for &b in chunk {
+            if b == b'\n' {
+                self.end_line();
             } else {
-                let mut vc: Vec<char> = current_state.buffer.chars().collect();
-                vc.remove(0);
-                current_state.buffer = vc.iter().collect();
+                self.line.push(b);
             }
         }
-        if full_match {
-            break
+    }
+
+    fn end_line(&mut self) {
+        self.totals.lines += 1;
+        match (self.line.first(), self.line.last()) {
+            (Some(first), Some(last)) => self.totals.total += combine(first.value.0, last.value.0),
+            _ => self.totals.missing.push(self.totals.lines),
         }
+        self.line.reset();
     }
-    v
-}
-
-pub fn day_1b() {
-    /*
-    given a string, start from index 0, read char by char
-        for each char:
-            if digit:
-                reach
-            else
-            check current buffer:
-                if empty:
-                    take cur char, check if any digit word starts with char
-                        if so:
-                            update buffer
-                        else:
-                            pass
-                else:
-                    combine buffer with new char
-                    check if updated string match digit word fully
-                        if so:
-                            reach conclusion
-                        else:
-                            pass
-
-    */
-    let file = File::open("data/day_1").unwrap();
-    let reader = BufReader::new(file);
-
-    let mut s = 0;
-    for line in reader.lines() {
-        let _current_state = MatchingWord::default();
-        let a = line.unwrap();
-
-        let first_v = find_digit(a.as_str(), false);
-        let sec_v = find_digit(a.as_str(), true);
-
-        let mut c_val = 0;
-        c_val += first_v * 10;
-        c_val += sec_v;
-        s += c_val;
-    }
-    println!("{}", s)
-}
\ No newline at end of file
+
+    /// Closes the last line if the input did not end with a newline. `Skip` and `Zero` give
+    /// the same total here, only `Error` differs.
+    pub fn finish(mut self, policy: MissingDigits) -> Result<StreamTotals> {
+        if self.line.offset() > 0 {
+            self.end_line();
+        }
+        let totals = self.totals;
+        match policy {
+            MissingDigits::Error if !totals.missing.is_empty() => Err(missing_digits(&totals.missing)),
+            _ => Ok(totals),
+        }
+    }
+}
+
+/// Streams `reader` through a [`CalibrationStream`] with one fixed buffer.
+pub fn calibrate_reader(calibrator: &Calibrator, mut reader: impl Read, policy: MissingDigits) -> Result<StreamTotals> {
+    let mut stream = CalibrationStream::new(calibrator);
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => stream.feed(&buffer[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    stream.finish(policy)
+}
+
+pub fn day_1a(lines: &[String], policy: MissingDigits) -> Result<u64> {
+    Ok(calibrate(lines, &calibrator(Part::A), policy)?.total())
+}
+
+pub fn day_1b(lines: &[String], policy: MissingDigits) -> Result<u64> {
+    Ok(calibrate(lines, &calibrator(Part::B), policy)?.total())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_A: &str = "1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+    const EXAMPLE_B: &str = "two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+
+    #[test]
+    fn part_a_example() {
+        let lines = Day1::parse(EXAMPLE_A).unwrap();
+        assert_eq!(Day1::part_a(&lines).unwrap(), Answer::U64(142));
+    }
+
+    #[test]
+    fn part_b_example() {
+        let lines = Day1::parse(EXAMPLE_B).unwrap();
+        assert_eq!(Day1::part_b(&lines).unwrap(), Answer::U64(281));
+    }
+
+    #[test]
+    fn calibrator_sees_overlapping_words() {
+        let calibrator = Calibrator::new(&Vocabulary::english());
+        assert_eq!(calibrator.value("zoneight234"), Some(14));
+        assert_eq!(calibrator.value("eightwo"), Some(82));
+        assert_eq!(calibrator.value("oneight"), Some(18));
+        assert_eq!(calibrator.value("xyz"), None);
+        assert_eq!(Calibrator::digits().value("two1nine"), Some(11));
+    }
+
+    #[test]
+    fn missing_digits_follow_the_policy() {
+        let lines = Day1::parse("a1\nxyz\n2b3\nnada").unwrap();
+        let calibrator = calibrator(Part::B);
+
+        let e = calibrate(&lines, &calibrator, MissingDigits::Error).unwrap_err();
+        assert_eq!(e.to_string(), "no digit in lines 2, 4");
+
+        let skipped = calibrate(&lines, &calibrator, MissingDigits::Skip).unwrap();
+        assert_eq!(skipped.values, vec![(1, 11), (3, 23)]);
+        assert_eq!(skipped.missing, vec![2, 4]);
+
+        let zeroed = calibrate(&lines, &calibrator, MissingDigits::Zero).unwrap();
+        assert_eq!(zeroed.values, vec![(1, 11), (2, 0), (3, 23), (4, 0)]);
+        assert_eq!(zeroed.total(), skipped.total());
+
+        // both parts apply the same policy
+        assert_eq!(Day1::part_a(&lines).unwrap_err().to_string(), Day1::part_b(&lines).unwrap_err().to_string());
+    }
+
+    #[test]
+    fn every_extractor_reports_missing_digits_alike() {
+        // one line is located, several are listed, past ten only the count of the rest is given
+        let cases = [
+            ("1\nxyz\n2", "line 2: no digit in line"),
+            ("a1\nxyz\n2b3\nnada", "no digit in lines 2, 4"),
+            (&"x\n".repeat(12)[..], "no digit in lines 1, 2, 3, 4, 5, 6, 7, 8, 9, 10 and 2 more"),
+        ];
+        let calibrator = calibrator(Part::B);
+        for (input, message) in cases {
+            let by_line = calibrate(&Day1::parse(input).unwrap(), &calibrator, MissingDigits::Error).unwrap_err();
+            let streamed = calibrate_reader(&calibrator, input.as_bytes(), MissingDigits::Error).unwrap_err();
+            assert_eq!((by_line.to_string(), streamed.to_string()), (message.to_string(), message.to_string()));
+        }
+    }
+
+    #[test]
+    fn stream_matches_line_by_line_across_chunk_sizes() {
+        let calibrator = calibrator(Part::B);
+        let expected = calibrate(&Day1::parse(EXAMPLE_B).unwrap(), &calibrator, MissingDigits::Error).unwrap();
+        for chunk in [1, 2, 3, 7, 64] {
+            let mut stream = CalibrationStream::new(&calibrator);
+            for piece in EXAMPLE_B.as_bytes().chunks(chunk) {
+                stream.feed(piece);
+            }
+            let totals = stream.finish(MissingDigits::Error).unwrap();
+            assert_eq!((totals.total, totals.lines), (expected.total(), 7));
+        }
+
+        let totals = calibrate_reader(&calibrator, "oneight\r\nxyz\n\n7\n".as_bytes(), MissingDigits::Skip).unwrap();
+        assert_eq!(totals, StreamTotals { total: 18 + 77, lines: 4, missing: vec![2, 3] });
+    }
+
+    #[test]
+    fn report_locates_first_and_last_token() {
+        let report = Calibrator::new(&Vocabulary::english()).report("abcone2threexyz").unwrap();
+        assert_eq!(report.first, Token { start: 3, end: 6, kind: TokenKind::Word, value: 1 });
+        assert_eq!(report.last, Token { start: 7, end: 12, kind: TokenKind::Word, value: 3 });
+        assert_eq!(report.value, 13);
+    }
+
+    #[test]
+    fn explain_underlines_tokens() {
+        let explained = Calibrator::new(&Vocabulary::english()).explain("abcone2threexyz\nnope");
+        assert_eq!(explained, "    1 | abcone2threexyz
+      |    ^^^ ^^^^^    1 (word at byte 3) .. 3 (word at byte 7) = 13
+    2 | nope
+      | no digit
+");
+    }
+
+    #[test]
+    fn custom_vocabulary_from_text() {
+        let vocabulary = Vocabulary::parse("# german\neins = 1\n\nzwei=2\ndrei = 3  # trailing\n").unwrap();
+        assert_eq!(vocabulary, [("eins", 1), ("zwei", 2), ("drei", 3)].into_iter().collect());
+        assert_eq!(Calibrator::new(&vocabulary).value("xzweiundeinsdreix"), Some(23));
+
+        let e = Vocabulary::parse("eins = 1\nzwei two").unwrap_err();
+        assert_eq!(e.to_string(), "line 2 col 6: unexpected input (Char)");
+    }
+
+    #[test]
+    fn large_vocabulary_values_do_not_overflow() {
+        let vocabulary = Vocabulary::parse("big = 500000000
+twenty = 20").unwrap();
+        let calibrator = Calibrator::new(&vocabulary);
+        assert_eq!(calibrator.value("xbigx"), Some(5_500_000_000));
+        assert_eq!(calibrator.value("twenty3"), Some(203));
+
+        let totals = calibrate_reader(&calibrator, "xbigx\ntwenty3\n".as_bytes(), MissingDigits::Error).unwrap();
+        assert_eq!(totals.total, 5_500_000_000 + 203);
+    }
+}
@@ -1,112 +1,692 @@
-use std::fs::File;
-use std::io::{BufReader, BufRead};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::iter::Iterator;
+use std::sync::Arc;
 
 use nom::{
     character::complete::{digit1, multispace0, space1},
     bytes::complete::tag,
     combinator::{map_res},
     multi::separated_list1,
-    sequence::{preceded, tuple},
-    IResult,
+    sequence::{pair, preceded, terminated, tuple},
 };
 use nom::character::complete::alpha1;
 
-struct Draw {
-    r: u32,
-    b: u32,
-    g: u32,
+use crate::error::{parse_line, Error, ErrorKind, IResult, ParseFailure, Result};
+use crate::solution::{Answer, Solution};
+
+/// A cube color: the three the puzzle names, or any other met in the input.
+///
+/// Colors compare, order and hash by name, so draws print and statistics list their colors
+/// in the same order whatever else has been parsed. [`Color::new`] is the way to name one:
+/// it never wraps red, green or blue in [`Color::Other`].
+#[derive(Clone)]
+pub enum Color {
+    Red,
+    Green,
+    Blue,
+    Other(Arc<str>),
 }
-struct Game {
-    id: u32,
-    draws: Vec<Draw>,
+
+impl Color {
+    /// The color called `name`.
+    pub fn new(name: &str) -> Color {
+        match name {
+            "red" => Color::Red,
+            "green" => Color::Green,
+            "blue" => Color::Blue,
+            _ => Color::Other(name.into()),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Color::Red => "red",
+            Color::Green => "green",
+            Color::Blue => "blue",
+            Color::Other(name) => name,
+        }
+    }
 }
 
-impl Game {
-    fn power(&self) -> u32 {
-        let mut min_draw = Draw {r:0, g:0,b:0};
-        for d in self.draws.iter() {
-            min_draw.r = min_draw.r.max(d.r);
-            min_draw.g = min_draw.g.max(d.g);
-            min_draw.b = min_draw.b.max(d.b);
+impl PartialEq for Color {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+impl Eq for Color {}
+
+impl PartialOrd for Color {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Color {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name().cmp(other.name())
+    }
+}
+
+impl Hash for Color {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name().hash(state)
+    }
+}
+
+impl fmt::Debug for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Color({})", self.name())
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// Cubes shown at once, by color. A color that is absent counts as zero cubes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Draw {
+    counts: BTreeMap<Color, u32>,
+}
+
+impl Draw {
+    pub fn get(&self, color: &Color) -> u32 {
+        self.counts.get(color).copied().unwrap_or(0)
+    }
+
+    pub fn insert(&mut self, color: Color, count: u32) {
+        self.counts.insert(color, count);
+    }
+
+    pub fn colors(&self) -> impl Iterator<Item = &Color> + '_ {
+        self.counts.keys()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Color, u32)> + '_ {
+        self.counts.iter().map(|(c, n)| (c, *n))
+    }
+}
+
+/// The input syntax, `3 blue, 4 red`, colors in name order. An empty draw prints as
+/// nothing, which [`parse_draw`] does not accept back.
+impl fmt::Display for Draw {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (color, count)) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{count} {color}")?;
         }
-        min_draw.r * min_draw.g * min_draw.b
+        Ok(())
     }
 }
 
-fn parse_number(input: &str) -> IResult<&str, u32> {
-    map_res(digit1, str::parse)(input)
+/// A color given twice keeps its first count.
+impl FromIterator<(Color, u32)> for Draw {
+    fn from_iter<I: IntoIterator<Item = (Color, u32)>>(iter: I) -> Self {
+        let mut counts = BTreeMap::new();
+        for (color, count) in iter {
+            counts.entry(color).or_insert(count);
+        }
+        Draw { counts }
+    }
 }
 
-fn parse_color(input: &str) -> IResult<&str, u32> {
-    preceded(multispace0, parse_number)(input)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub draws: Vec<Draw>,
 }
 
-fn parse_cube_count(input: &str) -> IResult<&str, (u32, &str)> {
-    let (input, (count, _, color)) = tuple((parse_number, space1, alpha1))(input)?;
-    Ok((input, (count, color)))
+/// One input line, `Game 1: 3 blue, 4 red; 2 green`, that [`parse_game`] reads back as the same game.
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Game {}: ", self.id)?;
+        for (i, draw) in self.draws.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{draw}")?;
+        }
+        Ok(())
+    }
 }
 
-fn parse_draw(input: &str) -> IResult<&str, Draw> {
-    let (input, ccs) = separated_list1(tag(", "), parse_cube_count)(input)?;
+impl Game {
+    /// The fewest cubes of each color that make every draw possible.
+    pub fn minimal_bag(&self) -> Bag {
+        let mut bag = Bag::default();
+        for (color, count) in self.draws.iter().flat_map(Draw::iter) {
+            bag.insert(color.clone(), bag.get(color).max(count));
+        }
+        bag
+    }
 
-    Ok((input, Draw {
-        r: ccs.iter().find(|(_, color)|*color == "red").map(|(c, _)|*c).unwrap_or(0),
-        g: ccs.iter().find(|(_, color)|*color == "green").map(|(c, _)|*c).unwrap_or(0),
-        b: ccs.iter().find(|(_, color)|*color == "blue").map(|(c, _)|*c).unwrap_or(0),
-    }))
+    /// The product of the minimal bag over every color the game shows. Part b's power is
+    /// fixed to red, green and blue instead, see [`day_2b`].
+    pub fn power(&self) -> u32 {
+        self.minimal_bag().iter().map(|(_, count)| count).product()
+    }
+
+    /// `None` when every draw fits in `bag`, colors within a draw checked in name order.
+    pub fn violation(&self, bag: &Bag) -> Option<Violation> {
+        self.draws.iter().enumerate().find_map(|(i, draw)| {
+            draw.iter().find(|(color, count)| *count > bag.get(color)).map(|(color, shown)| {
+                Violation { game: self.id, draw: i, color: color.clone(), shown, held: bag.get(color) }
+            })
+        })
+    }
 }
 
-fn parse_game(input: &str) -> IResult<&str, Game> {
+fn parse_number(input: &str) -> IResult<'_, u32> {
+    map_res(digit1, str::parse)(input)
+}
+
+fn parse_cube_count(input: &str) -> IResult<'_, (Color, u32)> {
+    let (input, (count, _, color)) = tuple((parse_number, space1, alpha1))(input)?;
+    Ok((input, (Color::new(color), count)))
+}
+
+pub fn parse_draw(input: &str) -> IResult<'_, Draw> {
+    let (input, counts) = separated_list1(tag(", "), parse_cube_count)(input)?;
+    Ok((input, counts.into_iter().collect()))
+}
+
+fn parse_game_with<'a>(input: &'a str, draw: impl FnMut(&'a str) -> IResult<'a, Draw>) -> IResult<'a, Game> {
     let (input, (id, _, draws)) = tuple(
         (
         preceded(multispace0, preceded(tag("Game "), parse_number)),
         preceded(tag(":"), multispace0),
-        separated_list1(tag("; "), parse_draw),
+        separated_list1(tag("; "), draw),
         )
     )(input)?;
 
     Ok((input, Game { id, draws }))
 }
 
-fn validate(truth: &Draw, draw: &Draw) -> bool {
-    draw.r <= truth.r && draw.g <= truth.g && draw.b <= truth.b
-}
-
-pub fn day_2a() {
-    let truth = Draw{r: 12, g: 13, b:14};
-    let file = File::open("data/day_2").unwrap();
-    let mut c = 0;
-    let reader = BufReader::new(file);
-    for line in reader.lines() {
-        /*
-        parse to draw, perform validation
-        */
-        let (_, game) = parse_game(line.unwrap().as_str()).unwrap();
-        let mut game_valid = true;
-        for d in game.draws {
-            if !validate(&truth, &d) {
-                game_valid = false;
-                break;
+pub fn parse_game(input: &str) -> IResult<'_, Game> {
+    parse_game_with(input, parse_draw)
+}
+
+/// [`parse_draw`] failing on a color named twice, where the lenient one keeps the first count.
+pub fn parse_draw_strict(input: &str) -> IResult<'_, Draw> {
+    let mut draw = Draw::default();
+    let mut rest = input;
+    loop {
+        let (at_color, count) = terminated(parse_number, space1)(rest)?;
+        let (after, name) = alpha1(at_color)?;
+        let color = Color::new(name);
+        if draw.counts.contains_key(&color) {
+            return ParseFailure::fail(at_color, ErrorKind::Invalid(format!("{color} appears twice in one draw")));
+        }
+        draw.insert(color, count);
+        match tag::<_, _, ParseFailure>(", ")(after) {
+            Ok((next, _)) => rest = next,
+            Err(_) => return Ok((after, draw)),
+        }
+    }
+}
+
+/// [`parse_game`] that must consume the whole line and rejects repeated colors within a draw.
+pub fn parse_game_strict(input: &str) -> IResult<'_, Game> {
+    let (rest, game) = parse_game_with(input, parse_draw_strict)?;
+    if !rest.is_empty() {
+        return ParseFailure::fail(rest, ErrorKind::Invalid(format!("unexpected `{rest}` after the last draw")));
+    }
+    Ok((rest, game))
+}
+
+/// How forgiving [`parse_games`] is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Strictness {
+    /// Whatever [`parse_game`] makes of each line, ignoring anything left after it. What
+    /// [`Day2`] parses with.
+    #[default]
+    Lenient,
+    /// Whole lines only, no color twice in a draw, and game ids strictly increasing.
+    Strict,
+}
+
+/// One game per line, every failure located by line and column.
+pub fn parse_games(input: &str, strictness: Strictness) -> Result<Vec<Game>> {
+    let mut games: Vec<Game> = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let game = match strictness {
+            Strictness::Lenient => parse_line(i, line, parse_game)?,
+            Strictness::Strict => parse_line(i, line, |line| {
+                let (rest, game) = parse_game_strict(line)?;
+                let (at_id, _) = pair(multispace0, tag("Game "))(line)?;
+                match games.last() {
+                    Some(previous) if game.id <= previous.id => ParseFailure::fail(
+                        at_id,
+                        ErrorKind::Invalid(format!("game {} comes after game {}", game.id, previous.id)),
+                    ),
+                    _ => Ok((rest, game)),
+                }
+            })?,
+        };
+        games.push(game);
+    }
+    Ok(games)
+}
+
+/// Cube counts a bag holds, a color it lacks counting as none.
+pub type Bag = Draw;
+
+pub fn validate(truth: &Bag, draw: &Draw) -> bool {
+    draw.iter().all(|(color, count)| count <= truth.get(color))
+}
+
+/// The first place a game shows more cubes than a bag holds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    pub game: u32,
+    /// Index into [`Game::draws`].
+    pub draw: usize,
+    pub color: Color,
+    pub shown: u32,
+    pub held: u32,
+}
+
+/// How a list of games fares against one bag.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Feasibility {
+    /// Ids of the games the bag could have produced, in input order.
+    pub feasible: Vec<u32>,
+    pub infeasible: Vec<Violation>,
+}
+
+/// Checks every game against every bag, one [`Feasibility`] per bag in the same order.
+pub fn feasibility(games: &[Game], bags: &[Bag]) -> Vec<Feasibility> {
+    bags.iter().map(|bag| {
+        let mut outcome = Feasibility::default();
+        for game in games {
+            match game.violation(bag) {
+                Some(v) => outcome.infeasible.push(v),
+                None => outcome.feasible.push(game.id),
             }
         }
-        if game_valid {
-            c += game.id;
+        outcome
+    }).collect()
+}
+
+/// The fewest cubes of each color that make every game possible.
+pub fn minimal_bag(games: &[Game]) -> Bag {
+    let mut bag = Bag::default();
+    for game in games {
+        for (color, count) in game.minimal_bag().iter() {
+            bag.insert(color.clone(), bag.get(color).max(count));
         }
     }
-    println!("{:?}", c)
-}
-pub fn day_2b() {
-    let _truth = Draw{r: 12, g: 13, b:14};
-    let file = File::open("data/day_2").unwrap();
-    let mut c = 0;
-    let reader = BufReader::new(file);
-    for line in reader.lines() {
-        /*
-        parse to draw, perform validation
-        */
-        let (_, game) = parse_game(line.unwrap().as_str()).unwrap();
-        c += game.power();
-    }
-    println!("{:?}", c)
+    bag
+}
+
+/// A color of the overall [`minimal_bag`] and the games that need that many cubes of it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pin {
+    pub color: Color,
+    pub count: u32,
+    pub games: Vec<u32>,
+}
+
+/// Which games pin down each color's minimum, in color order.
+pub fn pins(games: &[Game]) -> Vec<Pin> {
+    let minimal: Vec<Bag> = games.iter().map(Game::minimal_bag).collect();
+    minimal_bag(games).iter().map(|(color, count)| Pin {
+        color: color.clone(),
+        count,
+        games: games.iter().zip(&minimal).filter(|(_, m)| m.get(color) == count).map(|(g, _)| g.id).collect(),
+    }).collect()
+}
+
+/// A candidate bag, by its index in the list given to [`rank_bags`], and how many games it admits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ranked {
+    pub bag: usize,
+    pub admitted: usize,
+}
+
+/// Bags from most to fewest admitted games, ties keeping their given order.
+pub fn rank_bags(games: &[Game], bags: &[Bag]) -> Vec<Ranked> {
+    let mut ranking: Vec<Ranked> = feasibility(games, bags).iter().enumerate()
+        .map(|(bag, outcome)| Ranked { bag, admitted: outcome.feasible.len() })
+        .collect();
+    ranking.sort_by_key(|r| Reverse(r.admitted));
+    ranking
+}
+
+/// The bag part a asks about: 12 red, 13 green and 14 blue cubes.
+pub fn puzzle_bag() -> Bag {
+    [(Color::Red, 12), (Color::Green, 13), (Color::Blue, 14)].into_iter().collect()
+}
+
+/// How often a color turns up in a draw, a draw without it counting as zero cubes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorStats {
+    pub color: Color,
+    pub mean: f64,
+    pub max: u32,
+    /// Cube count -> number of draws showing that many.
+    pub histogram: BTreeMap<u32, usize>,
+    /// This color in [`Stats::estimate`].
+    pub estimate: u32,
+}
+
+/// Distributions over every draw of every game.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    pub games: usize,
+    pub draws: usize,
+    /// `(game id, number of draws)` in input order.
+    pub draws_per_game: Vec<(u32, usize)>,
+    /// In color order, covering every color any draw shows.
+    pub colors: Vec<ColorStats>,
+    /// Maximum-likelihood bag, see [`stats`].
+    pub estimate: Bag,
+}
+
+/// Summarizes `games`.
+///
+/// The bag estimate treats each cube shown as an independent sample from the bag. The
+/// maximum-likelihood share of a color is then its share of all cubes shown, and the estimate
+/// is that mix scaled to the smallest bag that still holds every draw. Its colors add up to
+/// exactly that size, the cubes left over after rounding each color down going to the largest
+/// remainders, ties in color order.
+pub fn stats(games: &[Game]) -> Stats {
+    let draws: Vec<&Draw> = games.iter().flat_map(|g| &g.draws).collect();
+    let observed = minimal_bag(games);
+
+    let totals: Vec<(Color, u64)> = observed.colors()
+        .map(|color| (color.clone(), draws.iter().map(|d| d.get(color) as u64).sum()))
+        .collect();
+    let shown: u64 = totals.iter().map(|(_, total)| total).sum();
+    let share = |total: u64| total as f64 / shown as f64;
+    let size = totals.iter()
+        .filter(|(_, total)| *total > 0)
+        .map(|(color, total)| (observed.get(color) as f64 / share(*total)).ceil())
+        .fold(0.0, f64::max);
+    let quotas: Vec<f64> = totals.iter().map(|(_, total)| share(*total) * size).collect();
+    let mut counts: Vec<u32> = totals.iter().zip(&quotas)
+        .map(|((color, _), quota)| observed.get(color).max(quota.floor() as u32))
+        .collect();
+    let mut by_remainder: Vec<usize> = (0..quotas.len()).collect();
+    by_remainder.sort_by(|&a, &b| quotas[b].fract().total_cmp(&quotas[a].fract()));
+    let left = (size as u32).saturating_sub(counts.iter().sum());
+    for &i in by_remainder.iter().take(left as usize) {
+        counts[i] += 1;
+    }
+    let estimate: Bag = totals.iter().map(|(color, _)| color.clone()).zip(counts).collect();
+
+    let colors = totals.iter().map(|(color, total)| {
+        let mut histogram = BTreeMap::new();
+        for draw in &draws {
+            *histogram.entry(draw.get(color)).or_insert(0) += 1;
+        }
+        ColorStats {
+            color: color.clone(),
+            mean: *total as f64 / draws.len() as f64,
+            max: observed.get(color),
+            histogram,
+            estimate: estimate.get(color),
+        }
+    }).collect();
+
+    Stats {
+        games: games.len(),
+        draws: draws.len(),
+        draws_per_game: games.iter().map(|g| (g.id, g.draws.len())).collect(),
+        colors,
+        estimate,
+    }
+}
+
+fn histogram_text(histogram: &BTreeMap<u32, usize>) -> String {
+    histogram.iter().map(|(count, draws)| format!("{count}:{draws}")).collect::<Vec<_>>().join(" ")
+}
+
+impl Stats {
+    pub fn to_table(&self) -> String {
+        let per_game = self.draws_per_game.iter().map(|(_, n)| *n);
+        let mut out = format!(
+            "{} games, {} draws, {}-{} draws per game\n{:<8} {:>8} {:>6} {:>8}  histogram (cubes:draws)\n",
+            self.games, self.draws, per_game.clone().min().unwrap_or(0), per_game.max().unwrap_or(0),
+            "color", "mean", "max", "estimate"
+        );
+        for c in &self.colors {
+            out.push_str(&format!(
+                "{:<8} {:>8.2} {:>6} {:>8}  {}\n",
+                c.color, c.mean, c.max, c.estimate, histogram_text(&c.histogram)
+            ));
+        }
+        out
+    }
+
+    /// One JSON object, the histograms keyed by cube count.
+    pub fn to_json(&self) -> String {
+        let per_game: Vec<String> = self.draws_per_game.iter()
+            .map(|(id, n)| format!(r#"{{"game":{id},"draws":{n}}}"#))
+            .collect();
+        let colors: Vec<String> = self.colors.iter().map(|c| {
+            let histogram: Vec<String> = c.histogram.iter().map(|(count, n)| format!(r#""{count}":{n}"#)).collect();
+            format!(
+                r#"    {{"color":"{}","mean":{},"max":{},"estimate":{},"histogram":{{{}}}}}"#,
+                c.color, c.mean, c.max, c.estimate, histogram.join(",")
+            )
+        }).collect();
+        format!(
+            "{{\n  \"games\":{},\n  \"draws\":{},\n  \"draws_per_game\":[{}],\n  \"colors\":[\n{}\n  ]\n}}",
+            self.games, self.draws, per_game.join(","), colors.join(",\n")
+        )
+    }
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Model = Vec<Game>;
+
+    fn parse(input: &str) -> Result<Self::Model> {
+        parse_games(input, Strictness::default())
+    }
+
+    fn part_a(games: &Self::Model) -> Result<Answer> {
+        Ok(day_2a(games)?.into())
+    }
+
+    fn part_b(games: &Self::Model) -> Result<Answer> {
+        Ok(day_2b(games).into())
+    }
+}
+
+/// Sums the ids of the games possible with the [`puzzle_bag`]. A game showing
+/// any other color is an error: the question says nothing about it.
+pub fn day_2a(games: &[Game]) -> Result<u32> {
+    let truth = puzzle_bag();
+    for game in games {
+        if let Some(color) = game.draws.iter().flat_map(Draw::colors).find(|c| !truth.colors().any(|t| t == *c)) {
+            return Err(Error::invalid(format!("game {} shows {color} cubes, the bag only holds red, green and blue", game.id)));
+        }
+    }
+    Ok(feasibility(games, &[truth])[0].feasible.iter().sum())
+}
+
+/// Sums the red x green x blue product of each game's minimal bag, a color the game never
+/// shows making it zero.
+pub fn day_2b(games: &[Game]) -> u32 {
+    games.iter().map(|game| {
+        let bag = game.minimal_bag();
+        bag.get(&Color::Red) * bag.get(&Color::Green) * bag.get(&Color::Blue)
+    }).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::XorShift;
+
+    const EXAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    #[test]
+    fn parse_game_reads_every_draw() {
+        let (_, game) = parse_game("Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red").unwrap();
+        assert_eq!(game.id, 3);
+        assert_eq!(game.draws.len(), 3);
+        let rgb = |d: &Draw| (d.get(&Color::Red), d.get(&Color::Green), d.get(&Color::Blue));
+        assert_eq!(rgb(&game.draws[0]), (20, 8, 6));
+        assert_eq!(rgb(&game.draws[2]), (1, 5, 0));
+    }
+
+    #[test]
+    fn colors_order_by_name() {
+        let mut colors = ["teal", "red", "amber", "blue", "green"].map(Color::new);
+        colors.sort();
+        assert_eq!(colors.each_ref().map(Color::name), ["amber", "blue", "green", "red", "teal"]);
+        assert_eq!(Color::new("amber"), colors[0]);
+        assert!(matches!(colors[3], Color::Red));
+
+        let (_, game) = parse_game("Game 1: 1 teal, 2 red, 3 amber").unwrap();
+        assert_eq!(game.to_string(), "Game 1: 3 amber, 2 red, 1 teal");
+    }
+
+    #[test]
+    fn part_b_keeps_the_puzzle_power() {
+        let games = Day2::parse("Game 1: 3 red, 4 blue\nGame 2: 1 red, 2 green, 3 blue, 5 yellow").unwrap();
+        assert_eq!((games[0].power(), games[1].power()), (12, 30));
+        assert_eq!(day_2b(&games), 6);
+    }
+
+    #[test]
+    fn other_colors_are_kept_and_reported() {
+        let (_, game) = parse_game("Game 9: 2 yellow, 1 red; 3 purple, 4 yellow").unwrap();
+        let yellow = Color::new("yellow");
+        assert_eq!(game.draws[1].get(&yellow), 4);
+        assert_eq!(Color::new("purple").to_string(), "purple");
+        assert_eq!(game.power(), 4 * 3);
+
+        let e = day_2a(&[game]).unwrap_err();
+        assert_eq!(e.to_string(), "game 9 shows yellow cubes, the bag only holds red, green and blue");
+    }
+
+    #[test]
+    fn feasibility_checks_every_bag() {
+        let games = Day2::parse(EXAMPLE).unwrap();
+        let roomy: Bag = [(Color::Red, 20), (Color::Green, 13), (Color::Blue, 15)].into_iter().collect();
+        let outcomes = feasibility(&games, &[puzzle_bag(), roomy]);
+
+        assert_eq!(outcomes[0].feasible, vec![1, 2, 5]);
+        assert_eq!(outcomes[0].infeasible, vec![
+            Violation { game: 3, draw: 0, color: Color::Red, shown: 20, held: 12 },
+            Violation { game: 4, draw: 2, color: Color::Blue, shown: 15, held: 14 },
+        ]);
+        assert_eq!(outcomes[1].feasible, vec![1, 2, 3, 4, 5]);
+        assert!(outcomes[1].infeasible.is_empty());
+    }
+
+    #[test]
+    fn minimal_bags_and_what_pins_them() {
+        let games = Day2::parse(EXAMPLE).unwrap();
+        let rgb = |b: &Bag| (b.get(&Color::Red), b.get(&Color::Green), b.get(&Color::Blue));
+        assert_eq!(rgb(&games[0].minimal_bag()), (4, 2, 6));
+        assert_eq!(rgb(&minimal_bag(&games)), (20, 13, 15));
+        assert_eq!(pins(&games), vec![
+            Pin { color: Color::Blue, count: 15, games: vec![4] },
+            Pin { color: Color::Green, count: 13, games: vec![3] },
+            Pin { color: Color::Red, count: 20, games: vec![3] },
+        ]);
+
+        let ranking = rank_bags(&games, &[puzzle_bag(), Bag::default(), minimal_bag(&games)]);
+        assert_eq!(ranking, vec![
+            Ranked { bag: 2, admitted: 5 },
+            Ranked { bag: 0, admitted: 3 },
+            Ranked { bag: 1, admitted: 0 },
+        ]);
+    }
+
+    #[test]
+    fn display_reproduces_the_input() {
+        let line = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red";
+        let (_, game) = parse_game(line).unwrap();
+        assert_eq!(game.to_string(), "Game 3: 6 blue, 8 green, 20 red; 5 blue, 13 green, 4 red; 5 green, 1 red");
+    }
+
+    #[test]
+    fn random_games_round_trip() {
+        let mut rng = XorShift::new(0x0002_2023);
+        let palette = ["red", "green", "blue", "yellow", "purple", "teal"].map(Color::new);
+        for _ in 0..500 {
+            let draws = (0..1 + rng.below(6)).map(|_| {
+                (0..1 + rng.below(palette.len() as u64)).map(|_| (palette[rng.below(6) as usize].clone(), rng.below(40) as u32)).collect()
+            }).collect();
+            let game = Game { id: rng.below(10_000) as u32, draws };
+            let text = game.to_string();
+            assert_eq!(parse_game(&text).unwrap(), ("", game), "{text}");
+        }
+    }
+
+    #[test]
+    fn lenient_parsing_is_the_default() {
+        let input = "Game 1: 3 blue \nGame 1: 2 red; junk";
+        assert_eq!(Day2::parse(input).unwrap().len(), 2);
+        assert!(parse_games(input, Strictness::Strict).is_err());
+    }
+
+    #[test]
+    fn strict_parsing_locates_each_violation() {
+        let error = |input: &str| parse_games(input, Strictness::Strict).unwrap_err().to_string();
+        assert_eq!(error("Game 1: 3 blue, 4 blue; 2 red"), "line 1 col 19: blue appears twice in one draw");
+        assert_eq!(error("Game 1: 3 blue; 2 red; junk"), "line 1 col 22: unexpected `; junk` after the last draw");
+        assert_eq!(error("Game 1: 3 blue\nGame 3: 1 red\nGame 2: 1 red"), "line 3 col 6: game 2 comes after game 3");
+        assert_eq!(error("Game 1: 3 blue\nGame x: 1 red"), "line 2 col 6: unexpected input (Digit)");
+
+        let games = parse_games("Game 2: 3 blue, 4 blue; junk\nGame 1: 1 red", Strictness::Lenient).unwrap();
+        assert_eq!(games[0].draws, vec![[(Color::Blue, 3)].into_iter().collect()]);
+        assert_eq!(games[1].id, 1);
+    }
+
+    #[test]
+    fn stats_summarize_every_draw() {
+        let games = Day2::parse("Game 1: 2 red, 1 blue; 4 red\nGame 2: 1 blue").unwrap();
+        let stats = stats(&games);
+        assert_eq!((stats.games, stats.draws, stats.draws_per_game.clone()), (2, 3, vec![(1, 2), (2, 1)]));
+
+        let red = &stats.colors[1];
+        assert_eq!((&red.color, red.mean, red.max), (&Color::Red, 2.0, 4));
+        assert_eq!(red.histogram, [(0, 1), (2, 1), (4, 1)].into_iter().collect());
+        // red is 3 in 4 cubes shown, 4 red take a bag of 6 at that mix: 4.5 red and 1.5 blue,
+        // the leftover cube going to blue, first in color order
+        assert_eq!(stats.estimate, [(Color::Red, 4), (Color::Blue, 2)].into_iter().collect());
+
+        assert_eq!(stats.to_table(), "2 games, 3 draws, 1-2 draws per game
+color        mean    max estimate  histogram (cubes:draws)
+blue         0.67      1        2  0:1 1:2
+red          2.00      4        4  0:1 2:1 4:1
+");
+        assert!(stats.to_json().contains(r#"{"color":"red","mean":2,"max":4,"estimate":4,"histogram":{"0":1,"2":1,"4":1}}"#));
+
+        // 159 cubes shown, 20 of the 61 red need a bag of 53: 16.67 blue, 16.00 green, 20.33 red
+        let example = super::stats(&Day2::parse(EXAMPLE).unwrap()).estimate;
+        assert_eq!(example, [(Color::Blue, 17), (Color::Green, 16), (Color::Red, 20)].into_iter().collect());
+    }
+
+    #[test]
+    fn part_a_example() {
+        let games = Day2::parse(EXAMPLE).unwrap();
+        assert_eq!(Day2::part_a(&games).unwrap(), Answer::U32(8));
+    }
+
+    #[test]
+    fn part_b_example() {
+        let games = Day2::parse(EXAMPLE).unwrap();
+        assert_eq!(Day2::part_b(&games).unwrap(), Answer::U32(2286));
+    }
 }
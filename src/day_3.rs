@@ -1,53 +1,71 @@
-use std::collections::{HashMap};
-use std::fs::File;
-use std::io::{BufReader, BufRead};
+use std::collections::BTreeSet;
+use std::ops::Range;
 
 use nom::bytes::complete::tag;
 use nom::character::complete::{char, digit1, none_of};
 use nom::combinator::{map_res};
 use nom::branch::alt;
-use nom::IResult;
 use nom::multi::many1;
 
-use nom::error::{Error, ErrorKind, ParseError};
-use crate::day_3::Ele::Dots;
+use nom::error::{ErrorKind, FromExternalError, ParseError};
+use crate::error::{self, parse_line, Error, IResult, ParseFailure, Result};
+use crate::solution::{Answer, Solution};
 
 #[derive(Clone, Copy)]
 struct IndexedStr<'a> (&'a str, u32);
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum Ele {
+/// What one parser step reads off a line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token {
     Dots,
-    Digit(u32),
+    Number(u32),
     Symbol,
     Gear,
 }
 
-fn parse_dots(input:IndexedStr) -> IResult<IndexedStr, Ele, Error<& str>> {
+/// One cell of a [`Grid`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ele {
+    Dots,
+    /// A digit of the part number `id`.
+    Part(PartId),
+    Symbol,
+    Gear,
+}
+
+impl Ele {
+    // a gear is only special in part b, anywhere else it is just another symbol
+    pub fn is_symbol(&self) -> bool {
+        matches!(self, Ele::Symbol | Ele::Gear)
+    }
+}
+
+fn parse_dots(input: IndexedStr<'_>) -> nom::IResult<IndexedStr<'_>, Token, ParseFailure<'_>> {
     let (input_1, dots) = many1(tag("."))(input.0)?;
-    Ok((IndexedStr(input_1,  input.1 + dots.len() as u32), Dots))
+    Ok((IndexedStr(input_1,  input.1 + dots.len() as u32), Token::Dots))
 }
 
-fn parse_symbol(input: IndexedStr) -> IResult<IndexedStr, Ele, Error<& str>> {
+fn parse_symbol(input: IndexedStr<'_>) -> nom::IResult<IndexedStr<'_>, Token, ParseFailure<'_>> {
     let (input_1, _o) = none_of("*0123456789.")(input.0)?;
-    Ok((IndexedStr(input_1, input.1 + 1), Ele::Symbol))
+    Ok((IndexedStr(input_1, input.1 + 1), Token::Symbol))
 }
 
-fn parse_gear(input: IndexedStr) -> IResult<IndexedStr, Ele, Error<& str>> {
+fn parse_gear(input: IndexedStr<'_>) -> nom::IResult<IndexedStr<'_>, Token, ParseFailure<'_>> {
     let (input_1, _o) = char('*')(input.0)?;
-    Ok((IndexedStr(input_1, input.1 + 1), Ele::Gear))
+    Ok((IndexedStr(input_1, input.1 + 1), Token::Gear))
 }
 
 
-fn parse_number(input: IndexedStr) -> IResult<IndexedStr, Ele, Error<& str>> {
+fn parse_number(input: IndexedStr<'_>) -> nom::IResult<IndexedStr<'_>, Token, ParseFailure<'_>> {
     let (input_1, digit) = map_res(digit1, str::parse::<u32>)(input.0)?;
-    let no_dec = digit.to_string().len();
-    Ok((IndexedStr(input_1, input.1 + no_dec as u32), Ele::Digit(digit)))
+    // the text's width, leading zeros included, not the value's
+    let width = input.0.len() - input_1.len();
+    Ok((IndexedStr(input_1, input.1 + width as u32), Token::Number(digit)))
 }
 
-impl <'a> ParseError<IndexedStr<'a>> for Error<&'a str> {
+impl <'a> ParseError<IndexedStr<'a>> for ParseFailure<'a> {
     fn from_error_kind(input: IndexedStr<'a>, kind: ErrorKind) -> Self {
-        Error::new(input.0, kind)
+        ParseFailure::from_error_kind(input.0, kind)
     }
 
     fn append(_input: IndexedStr, _kind: ErrorKind, other: Self) -> Self {
@@ -55,207 +73,287 @@ impl <'a> ParseError<IndexedStr<'a>> for Error<&'a str> {
     }
 }
 
-#[derive(Debug)]
-struct SingleLine{
-    numbers: [Ele; 140],
-    number_idx: Vec<u32>,
+impl <'a, E> FromExternalError<IndexedStr<'a>, E> for ParseFailure<'a> {
+    fn from_external_error(input: IndexedStr<'a>, kind: ErrorKind, e: E) -> Self {
+        ParseFailure::from_external_error(input.0, kind, e)
+    }
 }
 
-impl Default for SingleLine {
-    fn default() -> Self {
-        SingleLine{
-            numbers: [Ele::Dots; 140],
-            number_idx: vec![]
-        }
-    }
+/// A line as read, each token with the columns it covers.
+#[derive(Debug, Default)]
+pub struct SingleLine{
+    pub width: usize,
+    pub tokens: Vec<(Range<usize>, Token)>,
 }
 
-fn parse_single_line_a(input: &str) -> SingleLine {
+pub fn parse_single_line(input: &str) -> IResult<'_, SingleLine> {
     let mut remaining = IndexedStr(input, 0);
     let mut line_output = SingleLine::default();
-    let mut no_id = 0;
     while !remaining.0.is_empty() {
-        let (next, out) = alt((parse_dots, parse_symbol, parse_number))(remaining).unwrap();
-        match out {
-            Ele::Digit(d) => {
-                line_output.number_idx.push(d);
-                for i in 1..d.to_string().len() + 1 {
-                    let idx: usize = next.1 as usize - i;
-                    line_output.numbers[idx] = Ele::Digit(no_id)
-                }
-                no_id += 1;
-            }
-            Ele::Symbol => {
-                line_output.numbers[(next.1 - 1) as usize] = Ele::Symbol
-            }
-            _ => {}
-        }
+        let (next, out) = alt((parse_dots, parse_symbol, parse_number, parse_gear))(remaining)?;
+        line_output.tokens.push((remaining.1 as usize..next.1 as usize, out));
         remaining = next;
     }
+    line_output.width = remaining.1 as usize;
 
-    line_output
+    Ok((remaining.0, line_output))
 }
 
-fn parse_single_line_b(input: &str) -> SingleLine {
-    let mut remaining = IndexedStr(input, 0);
-    let mut line_output = SingleLine::default();
-    let mut no_id = 0;
-    while !remaining.0.is_empty() {
-        let (next, out) = alt((parse_dots, parse_symbol, parse_number, parse_gear))(remaining).unwrap();
-        match out {
-            Ele::Digit(d) => {
-                line_output.number_idx.push(d);
-                for i in 1..d.to_string().len() + 1 {
-                    let idx: usize = next.1 as usize - i;
-                    line_output.numbers[idx] = Ele::Digit(no_id)
-                }
-                no_id += 1;
-            }
-            Ele::Gear => {
-                line_output.numbers[(next.1 - 1) as usize] = Ele::Gear
-            }
-            _ => {}
-        }
-        remaining = next;
-    }
+/// Index of a number in [`Grid::parts`], stable for a given schematic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PartId(pub u32);
 
-    line_output
+/// A number in the schematic, covering columns `col_start..col_end` of `row`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PartNumber {
+    pub id: PartId,
+    pub row: usize,
+    pub col_start: usize,
+    pub col_end: usize,
+    pub value: u32,
 }
-fn accumulate_2lines_a(a: &SingleLine, b: &SingleLine, line_no_of_b: usize) -> HashMap<String, u32> {
-    let mut collected_digits: HashMap<String, u32> = HashMap::new();
-    for (i, e) in b.numbers.iter().enumerate() {
-        if *e == Ele::Symbol {
-            for adjacent in a.numbers[i-1..i+2].iter() {
-                match adjacent {
-                    Ele::Digit(key) => {
-                        let line_a = line_no_of_b - 1;
-                        collected_digits.insert(format!("{line_a}_{key}"), a.number_idx[*key as usize]);
-                    }
-                    _ => {}
-                }
-            }
-            match b.numbers[i - 1] {
-                Ele::Digit(key) => {
-                    collected_digits.insert(format!("{line_no_of_b}_{key}"), b.number_idx[key as usize]);
-                }
-                _ => {}
+
+/// A symbol and the part numbers touching it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Contact {
+    pub row: usize,
+    pub col: usize,
+    pub symbol: Ele,
+    pub parts: BTreeSet<PartId>,
+}
+
+/// An engine schematic of `rows x cols` cells, stored row by row.
+#[derive(Debug, Default)]
+pub struct Grid {
+    pub rows: usize,
+    pub cols: usize,
+    cells: Vec<Ele>,
+    /// Every number in reading order, indexed by [`PartId`].
+    pub parts: Vec<PartNumber>,
+}
+
+impl Grid {
+    /// One row per line, every line as wide as the first.
+    pub fn parse(input: &str) -> Result<Grid> {
+        let mut grid = Grid::default();
+        for (i, line) in input.lines().enumerate() {
+            let parsed = parse_line(i, line, parse_single_line)?;
+            if i == 0 {
+                grid.cols = parsed.width;
+            } else if parsed.width != grid.cols {
+                let kind = error::ErrorKind::Count { what: "columns", expected: grid.cols, found: parsed.width };
+                return Err(Error::new(kind).at_line(i + 1));
             }
-            match b.numbers[i + 1] {
-                Ele::Digit(key) => {
-                    collected_digits.insert(format!("{line_no_of_b}_{key}"), b.number_idx[key as usize]);
-                }
-                _ => {}
+            for (cols, token) in parsed.tokens {
+                let cell = match token {
+                    Token::Dots => Ele::Dots,
+                    Token::Symbol => Ele::Symbol,
+                    Token::Gear => Ele::Gear,
+                    Token::Number(value) => {
+                        let id = PartId(grid.parts.len() as u32);
+                        grid.parts.push(PartNumber { id, row: i, col_start: cols.start, col_end: cols.end, value });
+                        Ele::Part(id)
+                    }
+                };
+                grid.cells.extend(std::iter::repeat_n(cell, cols.len()));
             }
+            grid.rows += 1;
         }
+        Ok(grid)
+    }
 
-        let prev_e = a.numbers[i];
-        if prev_e == Ele::Symbol {
-            for adjacent in b.numbers[i-1..i+2].iter() {
-                match adjacent {
-                    Ele::Digit(key) => {
-                        collected_digits.insert(format!("{line_no_of_b}_{key}"), b.number_idx[*key as usize]);
-                    }
-                    _ => {}
-                }
-            }
+    pub fn row(&self, row: usize) -> &[Ele] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<Ele> {
+        if row < self.rows && col < self.cols {
+            Some(self.cells[row * self.cols + col])
+        } else {
+            None
         }
     }
-    collected_digits
-}
 
-fn accumulate_3lines(a: &SingleLine, b: &SingleLine, c: &SingleLine, line_c: usize) -> u32 {
-    let mut collected_ratios = 0;
-    let line_a = line_c - 2;
-    let line_b = line_c - 1;
-
-    for (i, e) in b.numbers.iter().enumerate() {
-        if *e == Ele::Gear {
-            let mut collected_digits: HashMap<String, u32> = HashMap::new();
-            for adjacent in a.numbers[i-1..i+2].iter() {
-                match adjacent {
-                    Ele::Digit(key) => {
-                        collected_digits.insert(format!("{line_a}_{key}"), a.number_idx[*key as usize]);
-                    }
-                    _ => {}
-                }
-            }
-            for adjacent in c.numbers[i-1..i+2].iter() {
-                match adjacent {
-                    Ele::Digit(key) => {
-                        collected_digits.insert(format!("{line_c}_{key}"), c.number_idx[*key as usize]);
-                    }
-                    _ => {}
-                }
-            }
+    /// Positions next to `(row, col)` that lie inside the grid, so a cell on a border or in a
+    /// corner simply has fewer of them.
+    pub fn neighbors(&self, row: usize, col: usize, connectivity: Connectivity) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let offsets: &[(isize, isize)] = match connectivity {
+            Connectivity::Four => &[(-1, 0), (0, -1), (0, 1), (1, 0)],
+            Connectivity::Eight => &[(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)],
+        };
+        offsets.iter().filter_map(move |&(dr, dc)| {
+            let (r, c) = (row.checked_add_signed(dr)?, col.checked_add_signed(dc)?);
+            (r < self.rows && c < self.cols).then_some((r, c))
+        })
+    }
 
-            match b.numbers[i - 1] {
-                Ele::Digit(key) => {
-                    collected_digits.insert(format!("{line_b}_{key}"), b.number_idx[key as usize]);
-                }
-                _ => {}
-            }
-            match b.numbers[i + 1] {
-                Ele::Digit(key) => {
-                    collected_digits.insert(format!("{line_b}_{key}"), b.number_idx[key as usize]);
-                }
-                _ => {}
-            }
+    pub fn part(&self, id: PartId) -> &PartNumber {
+        &self.parts[id.0 as usize]
+    }
+
+    /// The part numbers touching `(row, col)`.
+    pub fn parts_around(&self, row: usize, col: usize) -> BTreeSet<PartId> {
+        self.neighbors(row, col, Connectivity::Eight)
+            .filter_map(|(r, c)| match self.get(r, c) {
+                Some(Ele::Part(id)) => Some(id),
+                _ => None,
+            })
+            .collect()
+    }
 
-            if collected_digits.len() == 2 {
-                let x: u32 = collected_digits.values().product();
-                collected_ratios += x;
+    /// Every symbol in reading order, with the part numbers touching it.
+    pub fn contacts(&self) -> Vec<Contact> {
+        let mut contacts = vec![];
+        for row in 0..self.rows {
+            for (col, symbol) in self.row(row).iter().enumerate().filter(|(_, e)| e.is_symbol()) {
+                contacts.push(Contact { row, col, symbol: *symbol, parts: self.parts_around(row, col) });
             }
         }
+        contacts
+    }
+
+    /// Positions of the symbols touching part `id`.
+    pub fn symbols_around(&self, id: PartId) -> BTreeSet<(usize, usize)> {
+        let part = self.part(id);
+        (part.col_start..part.col_end)
+            .flat_map(|col| self.neighbors(part.row, col, Connectivity::Eight))
+            .filter(|&(r, c)| self.get(r, c).is_some_and(|e| e.is_symbol()))
+            .collect()
     }
-    collected_ratios
 }
 
-pub fn day_3a() {
-    /*
-    fill in 2 matric
-    */
-    let file = File::open("data/day_3").unwrap();
-    let _c = 0;
-    let reader = BufReader::new(file);
-
-    let mut prev = SingleLine::default();
-    let mut final_r = HashMap::new();
-
-    for (i, line) in reader.lines().enumerate() {
-        let cur = parse_single_line_a(line.unwrap().as_str());
-        let x = accumulate_2lines_a(&prev, &cur, i);
-        final_r.extend(x);
-        prev = cur;
+/// Which cells count as touching: sharing an edge, or an edge or a corner.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Connectivity {
+    Four,
+    #[default]
+    Eight,
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Model = Grid;
+
+    fn parse(input: &str) -> Result<Self::Model> {
+        Grid::parse(input)
+    }
+
+    fn part_a(grid: &Self::Model) -> Result<Answer> {
+        Ok(day_3a(grid).into())
+    }
+
+    fn part_b(grid: &Self::Model) -> Result<Answer> {
+        Ok(day_3b(grid).into())
     }
-    let ans: u32 = final_r.values().sum();
-    println!("{:?}", ans);
 }
-pub fn day_3b() {
-    /*
-    fill in 2 matric
-    */
-    let file = File::open("data/day_3").unwrap();
-    let mut ans = 0;
-    let reader = BufReader::new(file);
-
-    let mut line_a = SingleLine::default();
-    let mut line_b = SingleLine::default();
-
-    let mut line_no = 0;
-    for (i, line) in reader.lines().enumerate() {
-        let line_c = parse_single_line_b(line.unwrap().as_str());
-        let x = accumulate_3lines(&line_a, &line_b, &line_c, i + 2);
-        ans +=x;
-        line_a = line_b;
-        line_b = line_c;
-
-        line_no = i + 2;
+
+pub fn day_3a(grid: &Grid) -> u32 {
+    let touched: BTreeSet<PartId> = grid.contacts().into_iter().flat_map(|c| c.parts).collect();
+    touched.iter().map(|id| grid.part(*id).value).sum()
+}
+
+/// Sums the gear ratios, the product of the two numbers around a `*` touching exactly two.
+pub fn day_3b(grid: &Grid) -> u32 {
+    grid.contacts().iter()
+        .filter(|c| c.symbol == Ele::Gear && c.parts.len() == 2)
+        .map(|c| c.parts.iter().map(|id| grid.part(*id).value).product::<u32>())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    #[test]
+    fn parse_single_line_marks_numbers_and_symbols() {
+        let (_, line) = parse_single_line("617*...#.58").unwrap();
+        assert_eq!(line.width, 11);
+        assert_eq!(line.tokens, [
+            (0..3, Token::Number(617)), (3..4, Token::Gear), (4..7, Token::Dots), (7..8, Token::Symbol),
+            (8..9, Token::Dots), (9..11, Token::Number(58)),
+        ]);
+    }
+
+    #[test]
+    fn grid_takes_its_width_from_the_input() {
+        let wide = format!("{}*\n{}1", ".".repeat(200), ".".repeat(200));
+        let grid = Day3::parse(&wide).unwrap();
+        assert_eq!((grid.rows, grid.cols, grid.parts.len()), (2, 201, 1));
+        assert_eq!(grid.get(1, 200), Some(Ele::Part(PartId(0))));
+        assert_eq!(grid.get(2, 0), None);
+
+        let grid = Day3::parse("12.\n.34").unwrap();
+        assert_eq!(grid.row(1), [Ele::Dots, Ele::Part(PartId(1)), Ele::Part(PartId(1))]);
+        assert_eq!(grid.parts.iter().map(|p| p.value).collect::<Vec<_>>(), vec![12, 34]);
+
+        let padded = Day3::parse("007*\n....").unwrap();
+        assert_eq!((padded.cols, padded.parts[0].col_end, padded.parts[0].value), (4, 3, 7));
+        assert_eq!(day_3a(&padded), 7);
+
+        let e = Day3::parse("...\n..").unwrap_err();
+        assert_eq!(e.to_string(), "line 2: expected 3 columns, found 2");
+    }
+
+    #[test]
+    fn neighbors_stay_inside_the_grid() {
+        let grid = Day3::parse("...\n...\n...").unwrap();
+        let count = |row, col, connectivity| grid.neighbors(row, col, connectivity).count();
+        for (row, col) in [(0, 0), (0, 2), (2, 0), (2, 2)] {
+            assert_eq!((count(row, col, Connectivity::Eight), count(row, col, Connectivity::Four)), (3, 2));
+        }
+        for (row, col) in [(0, 1), (1, 0), (1, 2), (2, 1)] {
+            assert_eq!((count(row, col, Connectivity::Eight), count(row, col, Connectivity::Four)), (5, 3));
+        }
+        assert_eq!((count(1, 1, Connectivity::Eight), count(1, 1, Connectivity::Four)), (8, 4));
+        assert_eq!(grid.neighbors(0, 0, Connectivity::Four).collect::<Vec<_>>(), vec![(0, 1), (1, 0)]);
+
+        let single = Day3::parse("*").unwrap();
+        assert_eq!(single.neighbors(0, 0, Connectivity::Eight).count(), 0);
+    }
+
+    #[test]
+    fn symbols_on_corners_and_borders() {
+        let corners = Day3::parse("#2.3#\n4...5\n#6.7*").unwrap();
+        assert_eq!((day_3a(&corners), day_3b(&corners)), (27, 5 * 7));
+
+        let borders = Day3::parse(".2*3.\n*...$\n.4*5.").unwrap();
+        assert_eq!((day_3a(&borders), day_3b(&borders)), (14, 2 * 3 + 2 * 4 + 4 * 5));
     }
 
-    // one last loop to handle last line, special case because we are merging 3 line at a time and always do something like a look ahead
-    let line_c = SingleLine::default();
-    let x = accumulate_3lines(&line_a, &line_b, &line_c, line_no);
-    ans += x;
+    #[test]
+    fn part_numbers_know_their_symbols() {
+        let grid = Day3::parse(EXAMPLE).unwrap();
+        assert_eq!(grid.parts[0], PartNumber { id: PartId(0), row: 0, col_start: 0, col_end: 3, value: 467 });
+        assert_eq!(grid.part(PartId(1)).value, 114);
+        assert!(grid.symbols_around(PartId(1)).is_empty());
 
-    println!("{:?}", ans);
+        let contacts = grid.contacts();
+        assert_eq!(contacts[0], Contact { row: 1, col: 3, symbol: Ele::Gear, parts: [PartId(0), PartId(2)].into() });
+        let (row, col) = (contacts[0].row, contacts[0].col);
+        assert_eq!(grid.symbols_around(PartId(2)), [(row, col)].into());
+    }
+
+    #[test]
+    fn part_a_example() {
+        let grid = Day3::parse(EXAMPLE).unwrap();
+        assert_eq!(Day3::part_a(&grid).unwrap(), Answer::U32(4361));
+    }
+
+    #[test]
+    fn part_b_example() {
+        let grid = Day3::parse(EXAMPLE).unwrap();
+        assert_eq!(Day3::part_b(&grid).unwrap(), Answer::U32(467835));
+    }
 }
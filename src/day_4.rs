@@ -1,37 +1,37 @@
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufReader, BufRead};
 use nom::bytes::complete::{tag, take_until};
 use nom::character::complete::{digit1, multispace1, space0, space1};
 use nom::combinator::{map_res};
-use nom::{IResult, Parser};
 use nom::multi::{ separated_list1};
 use nom::sequence::tuple;
 
 use num::BigInt;
 
+use crate::error::{parse_line, ErrorKind, IResult, ParseFailure, Result};
+use crate::solution::{Answer, Solution};
 
-#[derive(Clone, Copy, Debug)]
-struct Hand {
-    card_id: u32,
-    winning: [u32;10],
-    given: [u32;25],
+
+#[derive(Clone, Debug)]
+pub struct Hand {
+    pub card_id: u32,
+    pub winning: Vec<u32>,
+    pub given: Vec<u32>,
 }
 
 impl Hand {
-    fn winning_hands(self: &Hand) -> u32 {
+    pub fn winning_hands(self: &Hand) -> u32 {
         let mut x = 0;
-        for i in self.given {
-            if self.winning.contains(&i) {
+        for i in self.given.iter() {
+            if self.winning.contains(i) {
                 x+=1;
             }
         }
         x
     }
-    fn points(self: &Hand) -> u32 {
+    pub fn points(self: &Hand) -> u32 {
         let mut x = 0;
-        for i in self.given {
-            if self.winning.contains(&i) {
+        for i in self.given.iter() {
+            if self.winning.contains(i) {
                 if x == 0 {
                     x = 1;
                 } else {
@@ -43,7 +43,7 @@ impl Hand {
 
     }
 
-    fn scratch_cards(self: &Hand, card_winning: &mut HashMap<u32, BigInt>) -> BigInt {
+    pub fn scratch_cards(self: &Hand, card_winning: &mut HashMap<u32, BigInt>) -> BigInt {
         let card_won = self.winning_hands();
         let mut no_of_card_won: BigInt  = BigInt::from(1);  // each card wins itself at the very least, thus start with 1
         for i in 0 ..card_won {
@@ -56,62 +56,112 @@ impl Hand {
     }
 }
 
-fn parse_number(input: &str) -> IResult<&str, u32> {
+fn parse_number(input: &str) -> IResult<'_, u32> {
     map_res(digit1, str::parse)(input)
 }
 
-fn parse_numbers<const C: usize>(input: &str) -> IResult<&str, [u32; C]> {
-    let (i, _o) = space0(input)?;
-    let (i, o) = separated_list1(space1, parse_number)(i)?;
-    let out: [u32; C] = o.try_into().unwrap();
-    Ok((i, out))
+fn parse_numbers(input: &str, expected: Option<usize>) -> IResult<'_, Vec<u32>> {
+    let (start, _o) = space0(input)?;
+    let (i, o) = separated_list1(space1, parse_number)(start)?;
+    match expected {
+        Some(expected) if expected != o.len() => {
+            ParseFailure::fail(start, ErrorKind::Count { what: "numbers", expected, found: o.len() })
+        }
+        _ => Ok((i, o)),
+    }
 }
 
-fn parse_hand(input: &str) -> IResult<&str, Hand> {
+/// How many winning and given numbers a card has, all cards of one input share it.
+pub type Shape = (usize, usize);
+
+pub fn parse_hand(input: &str, shape: Option<Shape>) -> IResult<'_, Hand> {
     let (input, (_, _, id, _)) = tuple((tag("Card"),multispace1, parse_number, tag(":")))(input)?;
 
     let (hand, winning) = take_until("|")(input)?;
     let (hand, _) = tag("|")(hand)?;
     let h = Hand {
         card_id: id,
-        winning: parse_numbers::<10>(winning)?.1,
-        given: parse_numbers::<25>(hand)?.1,
+        winning: parse_numbers(winning, shape.map(|s| s.0))?.1,
+        given: parse_numbers(hand, shape.map(|s| s.1))?.1,
     };
     Ok((hand, h))
 }
 
-pub fn day_4a() {
-    /*
-    fill in 2 matric
-    */
-    let file = File::open("data/day_4").unwrap();
-    let reader = BufReader::new(file);
-
-    let mut f = 0;
-    for (_i, line) in reader.lines().enumerate() {
-        let (_, hand) = parse_hand(line.unwrap().as_str()).unwrap();
-        f += hand.points();
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Model = Vec<Hand>;
+
+    fn parse(input: &str) -> Result<Self::Model> {
+        // the first card sets the shape, a card with fewer numbers is a broken line
+        let mut shape = None;
+        let mut hands = vec![];
+        for (i, line) in input.lines().enumerate() {
+            let hand = parse_line(i, line, |s| parse_hand(s, shape))?;
+            shape = Some((hand.winning.len(), hand.given.len()));
+            hands.push(hand);
+        }
+        Ok(hands)
+    }
+
+    fn part_a(hands: &Self::Model) -> Result<Answer> {
+        Ok(day_4a(hands).into())
     }
-    println!("{:?}", f);
-}
 
-pub fn day_4b() {
-    /*
-    fill in 2 matric
-    */
-    let file = File::open("data/day_4").unwrap();
-    let reader = BufReader::new(file);
+    fn part_b(hands: &Self::Model) -> Result<Answer> {
+        Ok(day_4b(hands).into())
+    }
+}
 
-    let mut lines: Vec<_> = reader.lines().map(|line| { line.unwrap() }).collect();
-    lines.reverse();
+pub fn day_4a(hands: &[Hand]) -> u32 {
+    hands.iter().map(|hand| hand.points()).sum()
+}
 
+pub fn day_4b(hands: &[Hand]) -> BigInt {
+    // walk backwards so every card a hand wins has already been scored
     let mut card_won = HashMap::new();
     let mut res: BigInt = BigInt::from(0);
-    for (_i, line) in lines.iter().enumerate() {
-        let (_, hand) = parse_hand(line.as_str()).unwrap();
+    for hand in hands.iter().rev() {
         res += hand.scratch_cards(&mut card_won);
     }
-    println!("{:?}", res);
+    res
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    #[test]
+    fn parse_hand_splits_winning_and_given() {
+        let (_, hand) = parse_hand("Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1", None).unwrap();
+        assert_eq!(hand.card_id, 3);
+        assert_eq!(hand.winning, vec![1, 21, 53, 59, 44]);
+        assert_eq!(hand.given, vec![69, 82, 63, 72, 16, 21, 14, 1]);
+        assert_eq!(hand.points(), 2);
+    }
+
+    #[test]
+    fn short_card_reports_count() {
+        let e = Day4::parse("Card 1: 1 2 | 3 4 5\nCard 2: 1 2 | 3 4").unwrap_err();
+        assert_eq!(e.to_string(), "line 2 col 15: expected 3 numbers, found 2");
+    }
+
+    #[test]
+    fn part_a_example() {
+        let hands = Day4::parse(EXAMPLE).unwrap();
+        assert_eq!(Day4::part_a(&hands).unwrap(), Answer::U32(13));
+    }
 
+    #[test]
+    fn part_b_example() {
+        let hands = Day4::parse(EXAMPLE).unwrap();
+        assert_eq!(Day4::part_b(&hands).unwrap(), Answer::BigInt(BigInt::from(30)));
+    }
+}
@@ -1,27 +1,25 @@
-use std::fs::File;
-use std::io::BufReader;
-use std::io::BufRead;
-
-
 use nom::bytes::complete::{tag, take_until};
 use nom::character::complete::{digit1, multispace0, space0, space1};
-use nom::combinator::{map, map_res};
+use nom::combinator::map_res;
 use nom::multi::{ separated_list1};
-use nom::{IResult, Parser};
+use nom::Parser;
 use nom::sequence::{preceded, tuple};
 
 use crate::day_5::RangeOverlap::{Full, NotInRange, Partial};
+use crate::error::{parse_text, Error, ErrorKind, IResult, ParseFailure, Result};
+use crate::solution::{Answer, Solution};
 
 #[derive(Debug)]
-struct LeEntry {
-    dest: u64,
-    source: u64,
-    range: u64
+pub struct LeEntry {
+    pub dest: u64,
+    pub source: u64,
+    pub range: u64
 }
 
 #[derive(Debug)]
-struct LeMap {
-    entries: Vec<LeEntry>
+pub struct LeMap {
+    /// Sorted by `source`.
+    pub entries: Vec<LeEntry>
 }
 
 enum RangeOverlap {
@@ -30,32 +28,26 @@ enum RangeOverlap {
     Partial{dest_range: (u64, u64), residual: (u64, u64)}
 }
 impl LeEntry {
+    /// Maps a target range starting at or after `self.source`, whatever does not fit is left as residual.
     fn compute_dest_range(&self, target_key: u64, target_range: u64) -> RangeOverlap {
-        if self.source > target_key {
-            // if the entry has past target, means no match, just return target range as is
-            return Full {dest_range: (target_key, target_range)}
+        if target_key >= self.source + self.range {
+            return NotInRange {residual: (target_key, target_range)}
         }
 
         let source_offset = target_key - self.source;
+        let dest_start =  source_offset + self.dest;
+        let dest_in_range = self.range - source_offset;
 
-        if target_key <= (self.source + self.range) {
-            let dest_start =  source_offset + self.dest;
-            let dest_in_range = self.range - source_offset;
-            let dest_range = if target_range <= dest_in_range { target_range } else { dest_in_range };
-
-            if dest_range < target_range {
-                Partial {dest_range: (dest_start, dest_range), residual: (target_key + dest_range, target_range - dest_range)}
-            } else {
-                Full {dest_range: (dest_start, dest_range)}
-            }
+        if dest_in_range < target_range {
+            Partial {dest_range: (dest_start, dest_in_range), residual: (target_key + dest_in_range, target_range - dest_in_range)}
         } else {
-            NotInRange {residual: (target_key, target_range)}
+            Full {dest_range: (dest_start, target_range)}
         }
     }
 }
 
 impl LeMap {
-    fn find(&self, key: u64) -> u64 {
+    pub fn find(&self, key: u64) -> u64 {
         match self.entries.iter().find(|e| key >= e.source && key < (e.source + e.range)) {
             None => {
                 key
@@ -66,37 +58,16 @@ impl LeMap {
         }
     }
 
-    fn find_range(&self, target: (u64, u64)) -> Vec<(u64, u64)> {
+    pub fn find_range(&self, target: (u64, u64)) -> Vec<(u64, u64)> {
         let (key, range) = target;
-        let mut search_start = 0;
-        let mut search_end = self.entries.len() - 1;
-
-        let mut i = (search_end + search_start) / 2; // floor
-        loop {
-
-            let cur = &self.entries[i];
-            let next = &self.entries[i+1];
 
-            if key >= cur.source && key <= next.source {
-                break
-            } else if key >= cur.source {
-                search_start = i + 1
-            } else {
-                search_end = i
-            }
-
-            if search_end == search_start {
-                i = search_end;
-                break;
-            }
-
-            i = (search_end + search_start) / 2;
-        }
+        // entries are sorted and do not overlap, skip straight to the first one not entirely before key
+        let mut i = self.entries.partition_point(|e| e.source + e.range <= key);
 
         /*
         given a starting index and a starting target
 
-        check if target start is out of range
+        any gap before the entry maps to itself
 
         compute_dest_range
             full -> add the range to output, break
@@ -107,13 +78,19 @@ impl LeMap {
         let mut search_k = key;
         let mut search_range = range;
 
-        loop {
-            let entry = &self.entries[i];
-
-            // this range does not exists in mapping
-            if search_k > (entry.source + entry.range) {
+        while search_range > 0 {
+            let Some(entry) = self.entries.get(i) else {
+                // past the last entry, the rest does not exist in mapping
                 output.push((search_k, search_range));
                 break;
+            };
+
+            if search_k < entry.source {
+                let gap = (entry.source - search_k).min(search_range);
+                output.push((search_k, gap));
+                search_k += gap;
+                search_range -= gap;
+                continue;
             }
 
             match entry.compute_dest_range(search_k, search_range) {
@@ -121,7 +98,10 @@ impl LeMap {
                     output.push(dest_range);
                     break;
                 }
-                NotInRange { residual: _ } => {}
+                NotInRange { residual } => {
+                    search_k = residual.0;
+                    search_range = residual.1;
+                }
                 Partial { dest_range, residual } => {
                     output.push(dest_range);
                     search_k = residual.0;
@@ -135,81 +115,49 @@ impl LeMap {
     }
 }
 
-fn parse_number(input: &str) -> IResult<&str, u64> {
+fn parse_number(input: &str) -> IResult<'_, u64> {
     map_res(digit1, str::parse)(input)
 }
 
-fn parse_numbers(input: &str) -> IResult<&str, Vec<u64>> {
+fn parse_numbers(input: &str) -> IResult<'_, Vec<u64>> {
     let (i, _o) = space0(input)?;
     let (i, o) = separated_list1(space1, parse_number)(i)?;
     Ok((i, o))
 }
 
-fn parse_init_seeds(input: &str) -> IResult<&str, Vec<u64>> {
+fn parse_init_seeds(input: &str) -> IResult<'_, Vec<u64>> {
     let (o, _) = tag("seeds:")(input)?;
     let (o, seeds_s) = take_until("\n")(o)?;
     let (_, seeds) = parse_numbers(seeds_s)?;
     Ok((o, seeds))
 }
 
-fn parse_le_entry(input: &str) -> IResult<&str, LeEntry> {
-    map(preceded(multispace0, parse_numbers), |nos| {
-        LeEntry {
-            dest: nos[0],
-            source: nos[1],
-            range: nos[2],
-        }
-    })(input)
+fn parse_le_entry(input: &str) -> IResult<'_, LeEntry> {
+    let (start, _) = multispace0(input)?;
+    let (o, nos) = parse_numbers(start)?;
+    match nos[..] {
+        [dest, source, range] => Ok((o, LeEntry { dest, source, range })),
+        _ => ParseFailure::fail(start, ErrorKind::Count { what: "numbers", expected: 3, found: nos.len() }),
+    }
 }
 
-fn parse_map<'a>(input: &'a str, expected_header: &'a str) -> IResult<&'a str, LeMap> {
+pub fn parse_map<'a>(input: &'a str, expected_header: &'a str) -> IResult<'a, LeMap> {
     let header_part = preceded(multispace0, tag(expected_header));
     let (o, mut entries) = preceded(header_part, separated_list1(tag("\n"), parse_le_entry)).parse(input)?;
 
-    entries.sort_by(|a, b|a.source.partial_cmp(&b.source).unwrap());
+    entries.sort_by_key(|e| e.source);
 
     Ok((o, LeMap{entries }))
 }
 
-pub fn day_5a() {
-    let file = File::open("data/day_5").unwrap();
-    let reader = BufReader::new(file);
-    let string: String = reader.lines().map(|x| x.unwrap()).collect::<Vec<String>>().join("\n");
-    let all_txt: & str = string.as_str();
-
-    let (_, (seeds, seed_soil, soil_fert, fert_water, water_light, light_temp, temp_humid, humid_loc)) =
-        tuple((parse_init_seeds,
-           |s|parse_map(s, "seed-to-soil map:"),
-           |s|parse_map(s, "soil-to-fertilizer map:"),
-           |s|parse_map(s, "fertilizer-to-water map:"),
-           |s|parse_map(s, "water-to-light map:"),
-           |s|parse_map(s, "light-to-temperature map:"),
-           |s|parse_map(s, "temperature-to-humidity map:"),
-           |s|parse_map(s, "humidity-to-location map:"),
-    ))(all_txt).unwrap();
-
-    let locs = seeds.iter().map(|s| {
-        let soil = seed_soil.find(*s);
-        let fert = soil_fert.find(soil);
-        let water = fert_water.find(fert);
-        let light = water_light.find(water);
-        let temp = light_temp.find(light);
-        let humid = temp_humid.find(temp);
-        
-        humid_loc.find(humid)
-    });
-
-    let smallest = locs.min().unwrap();
-
-    println!("{:?}", smallest)
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    /// seed-to-soil through humidity-to-location, in order.
+    pub maps: Vec<LeMap>,
 }
-pub fn day_5b() {
-    let file = File::open("data/day_5").unwrap();
-    let reader = BufReader::new(file);
-    let string: String = reader.lines().map(|x| x.unwrap()).collect::<Vec<String>>().join("\n");
-    let all_txt: & str = string.as_str();
 
-    let (_, (seeds, seed_soil, soil_fert, fert_water, water_light, light_temp, temp_humid, humid_loc)) =
+pub fn parse_almanac(input: &str) -> IResult<'_, Almanac> {
+    let (o, (seeds, seed_soil, soil_fert, fert_water, water_light, light_temp, temp_humid, humid_loc)) =
         tuple((parse_init_seeds,
                |s|parse_map(s, "seed-to-soil map:"),
                |s|parse_map(s, "soil-to-fertilizer map:"),
@@ -218,31 +166,121 @@ pub fn day_5b() {
                |s|parse_map(s, "light-to-temperature map:"),
                |s|parse_map(s, "temperature-to-humidity map:"),
                |s|parse_map(s, "humidity-to-location map:"),
-        ))(all_txt).unwrap();
+        ))(input)?;
+
+    let maps = vec![seed_soil, soil_fert, fert_water, water_light, light_temp, temp_humid, humid_loc];
+    Ok((o, Almanac { seeds, maps }))
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Model = Almanac;
 
-    /*
+    fn parse(input: &str) -> Result<Self::Model> {
+        parse_text(input, parse_almanac)
+    }
+
+    fn part_a(almanac: &Self::Model) -> Result<Answer> {
+        Ok(day_5a(almanac)?.into())
+    }
 
-    */
+    fn part_b(almanac: &Self::Model) -> Result<Answer> {
+        Ok(day_5b(almanac)?.into())
+    }
+}
 
-    let seeds_range: Vec<&[u64]> = seeds.as_slice().windows(2).collect();
+pub fn day_5a(almanac: &Almanac) -> Result<u64> {
+    let locs = almanac.seeds.iter().map(|s| {
+        almanac.maps.iter().fold(*s, |key, m| m.find(key))
+    });
+
+    locs.min().ok_or_else(|| Error::invalid("no seeds"))
+}
+pub fn day_5b(almanac: &Almanac) -> Result<u64> {
+    // seeds come in (start, length) pairs
+    let seeds_range: Vec<&[u64]> = almanac.seeds.chunks_exact(2).collect();
 
     let mut locs = vec![];
     for seed_r in seeds_range {
         let s = seed_r[0];
         let r = seed_r[1];
 
-        let final_locs: Vec<(u64, u64)> = seed_soil.find_range((s, r))
-            .iter().flat_map(|i| soil_fert.find_range(*i))
-            .flat_map(|i| fert_water.find_range(i))
-            .flat_map(|i| water_light.find_range(i))
-            .flat_map(|i| light_temp.find_range(i))
-            .flat_map(|i| temp_humid.find_range(i))
-            .flat_map(|i| humid_loc.find_range(i)).collect();
+        // push the ranges through every map in order: seed -> soil -> ... -> location
+        let final_locs: Vec<(u64, u64)> = almanac.maps.iter().fold(vec![(s, r)], |ranges, m| {
+            ranges.into_iter().flat_map(|i| m.find_range(i)).collect()
+        });
+
+        if let Some(m) = final_locs.iter().map(|(s, _r)|*s).min() {
+            locs.push(m);
+        }
+    }
+    locs.into_iter().min().ok_or_else(|| Error::invalid("no seed ranges"))
+}
 
-        let m = final_locs.iter().map(|(s, _r)|*s).min();
-        locs.push(m.unwrap());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
+    #[test]
+    fn parse_map_sorts_entries() {
+        let (_, map) = parse_map("seed-to-soil map:\n50 98 2\n52 50 48", "seed-to-soil map:").unwrap();
+        assert_eq!(map.entries.iter().map(|e| e.source).collect::<Vec<_>>(), vec![50, 98]);
+        assert_eq!(map.find(79), 81);
+        assert_eq!(map.find(10), 10);
     }
-    let smallest = locs.iter().min().unwrap();
 
-    println!("{:?}", smallest)
+    #[test]
+    fn find_range_splits_across_entries_and_gaps() {
+        let (_, map) = parse_map("m:\n50 98 2\n52 50 48", "m:").unwrap();
+        assert_eq!(map.find_range((45, 10)), vec![(45, 5), (52, 5)]);
+        assert_eq!(map.find_range((96, 6)), vec![(98, 2), (50, 2), (100, 2)]);
+    }
+
+    #[test]
+    fn part_a_example() {
+        let almanac = Day5::parse(EXAMPLE).unwrap();
+        assert_eq!(Day5::part_a(&almanac).unwrap(), Answer::U64(35));
+    }
+
+    #[test]
+    fn part_b_example() {
+        let almanac = Day5::parse(EXAMPLE).unwrap();
+        assert_eq!(Day5::part_b(&almanac).unwrap(), Answer::U64(46));
+    }
 }
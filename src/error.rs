@@ -0,0 +1,141 @@
+use std::fmt;
+use std::io;
+
+use nom::error::{ErrorKind as NomKind, FromExternalError, ParseError};
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    Io(io::Error),
+    /// A nom combinator did not match.
+    Parse(NomKind),
+    Count { what: &'static str, expected: usize, found: usize },
+    Invalid(String),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::Io(e) => write!(f, "{e}"),
+            ErrorKind::Parse(kind) => write!(f, "unexpected input ({})", kind.description()),
+            ErrorKind::Count { what, expected, found } => write!(f, "expected {expected} {what}, found {found}"),
+            ErrorKind::Invalid(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+/// Crate-wide error, positioned as precisely as the failing step knows. Lines and columns are 1-based.
+#[derive(Debug)]
+pub struct Error {
+    pub day: Option<u32>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub kind: ErrorKind,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn new(kind: ErrorKind) -> Error {
+        Error { day: None, line: None, column: None, kind }
+    }
+
+    pub fn invalid(msg: impl Into<String>) -> Error {
+        Error::new(ErrorKind::Invalid(msg.into()))
+    }
+
+    pub fn at_line(mut self, line: usize) -> Error {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn in_day(mut self, day: u32) -> Error {
+        self.day = Some(day);
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut location = vec![];
+        if let Some(day) = self.day {
+            location.push(format!("day_{day}"));
+        }
+        if let Some(line) = self.line {
+            location.push(format!("line {line}"));
+        }
+        if let Some(column) = self.column {
+            location.push(format!("col {column}"));
+        }
+        if location.is_empty() {
+            write!(f, "{}", self.kind)
+        } else {
+            write!(f, "{}: {}", location.join(" "), self.kind)
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::new(ErrorKind::Io(e))
+    }
+}
+
+/// Error type of the nom parsers: what went wrong and the input left at that point, which is
+/// turned into a line and column once the full text is known.
+#[derive(Debug)]
+pub struct ParseFailure<'a> {
+    pub input: &'a str,
+    pub kind: ErrorKind,
+}
+
+pub type IResult<'a, O> = nom::IResult<&'a str, O, ParseFailure<'a>>;
+
+impl<'a> ParseFailure<'a> {
+    /// A semantic failure that nom cannot express, reported as `nom::Err::Failure` so `alt` does not backtrack past it.
+    pub fn fail<O>(input: &'a str, kind: ErrorKind) -> IResult<'a, O> {
+        Err(nom::Err::Failure(ParseFailure { input, kind }))
+    }
+
+    fn into_error(self, full: &str) -> Error {
+        // parsers hand out sub-slices of `full`, so the offset is the pointer distance
+        let offset = (self.input.as_ptr() as usize)
+            .saturating_sub(full.as_ptr() as usize)
+            .min(full.len());
+        let before = &full[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = offset - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+        Error { day: None, line: Some(line), column: Some(column), kind: self.kind }
+    }
+}
+
+impl<'a> ParseError<&'a str> for ParseFailure<'a> {
+    fn from_error_kind(input: &'a str, kind: NomKind) -> Self {
+        ParseFailure { input, kind: ErrorKind::Parse(kind) }
+    }
+
+    fn append(_input: &'a str, _kind: NomKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<'a, E> FromExternalError<&'a str, E> for ParseFailure<'a> {
+    fn from_external_error(input: &'a str, kind: NomKind, _e: E) -> Self {
+        ParseFailure { input, kind: ErrorKind::Parse(kind) }
+    }
+}
+
+/// Runs `parser` over the whole of `text`, locating any failure by line and column.
+pub fn parse_text<'a, O>(text: &'a str, mut parser: impl FnMut(&'a str) -> IResult<'a, O>) -> Result<O> {
+    match parser(text) {
+        Ok((_, o)) => Ok(o),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(e.into_error(text)),
+        Err(nom::Err::Incomplete(_)) => Err(Error::new(ErrorKind::Parse(NomKind::Eof))),
+    }
+}
+
+/// [`parse_text`] for one line of a line-oriented input, `line_no` being its 0-based index.
+pub fn parse_line<'a, O>(line_no: usize, line: &'a str, parser: impl FnMut(&'a str) -> IResult<'a, O>) -> Result<O> {
+    parse_text(line, parser).map_err(|e| e.at_line(line_no + 1))
+}
@@ -0,0 +1,98 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Where a day's puzzle input comes from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    /// `data/day_N` under [`data_dir`].
+    Default,
+    Path(PathBuf),
+    Stdin,
+    /// For callers that already hold the text, e.g. tests.
+    Inline(String),
+}
+
+impl InputSource {
+    /// Interprets a `--input` value, `-` meaning stdin.
+    pub fn from_arg(arg: &str) -> InputSource {
+        if arg == "-" {
+            InputSource::Stdin
+        } else {
+            InputSource::Path(PathBuf::from(arg))
+        }
+    }
+
+    pub fn load(&self, day: u32) -> io::Result<String> {
+        match self {
+            InputSource::Default => read_file(&default_path(day)),
+            InputSource::Path(path) => read_file(path),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+            InputSource::Inline(input) => Ok(input.clone()),
+        }
+    }
+}
+
+/// `$AOC_DATA_DIR` if set, otherwise `data/` at the crate root, so the binary works from any directory.
+pub fn data_dir() -> PathBuf {
+    match env::var_os("AOC_DATA_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("data"),
+    }
+}
+
+pub fn default_path(day: u32) -> PathBuf {
+    data_dir().join(format!("day_{day}"))
+}
+
+pub fn read_file(path: &Path) -> io::Result<String> {
+    // io errors do not carry the path, without it "No such file or directory" is not much help
+    fs::read_to_string(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_arg_reads_dash_as_stdin() {
+        assert_eq!(InputSource::from_arg("-"), InputSource::Stdin);
+        assert_eq!(InputSource::from_arg("data/day_1"), InputSource::Path(PathBuf::from("data/day_1")));
+    }
+
+    #[test]
+    fn load_reads_inline_and_path_sources() {
+        assert_eq!(InputSource::Inline("1abc2".to_string()).load(1).unwrap(), "1abc2");
+
+        let path = env::temp_dir().join(format!("aoc_input_test_{}", std::process::id()));
+        fs::write(&path, "Game 1: 3 blue").unwrap();
+        let loaded = InputSource::Path(path.clone()).load(2);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), "Game 1: 3 blue");
+
+        let e = InputSource::Path(path.clone()).load(2).unwrap_err();
+        assert!(e.to_string().starts_with(&format!("{}: ", path.display())), "{e}");
+    }
+
+    #[test]
+    fn data_dir_honors_the_environment() {
+        // the only test touching AOC_DATA_DIR, so it cannot race another one
+        let previous = env::var_os("AOC_DATA_DIR");
+        env::set_var("AOC_DATA_DIR", "/tmp/aoc-elsewhere");
+        let (dir, day) = (data_dir(), default_path(3));
+        env::remove_var("AOC_DATA_DIR");
+        let fallback = data_dir();
+        if let Some(previous) = previous {
+            env::set_var("AOC_DATA_DIR", previous);
+        }
+
+        assert_eq!(dir, PathBuf::from("/tmp/aoc-elsewhere"));
+        assert_eq!(day, PathBuf::from("/tmp/aoc-elsewhere/day_3"));
+        assert_eq!(fallback, Path::new(env!("CARGO_MANIFEST_DIR")).join("data"));
+    }
+}
@@ -0,0 +1,21 @@
+//! Advent of Code 2023 solutions.
+//!
+//! Each `day_N` module holds that day's model, nom parsers and a type implementing
+//! [`solution::Solution`]; [`runner::SOLVERS`] registers them for the binary.
+
+pub mod answers;
+pub mod automaton;
+pub mod bench;
+pub mod day_1;
+pub mod day_2;
+pub mod day_3;
+pub mod day_4;
+pub mod day_5;
+pub mod error;
+pub mod input;
+pub mod output;
+pub mod rng;
+pub mod runner;
+pub mod scaffold;
+pub mod scanner;
+pub mod solution;
@@ -1,20 +1,341 @@
-mod day_1;
-mod day_2;
-mod day_3;
-mod day_4;
-mod day_5;
 
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
+use adventofcode2023::answers::{self, Answers};
+use adventofcode2023::day_1;
+use adventofcode2023::day_2::{self, Strictness};
+use adventofcode2023::bench;
+use adventofcode2023::input::{self, InputSource};
+use adventofcode2023::output::{self, Format};
+use adventofcode2023::runner::{self, Part};
+use adventofcode2023::scaffold;
 
+const USAGE: &str = "usage:
+    adventofcode2023 run (--day <N> [--part a|b] [--input <path>|-] | --all) [--format text|json|csv]
+    adventofcode2023 run --day 1 [--part a|b] [--input <path>|-] --explain
+    adventofcode2023 run --day 2 [--part a|b] [--input <path>|-] --strict [--format text|json|csv]
+    adventofcode2023 bench (--day <N> [--part a|b] [--input <path>|-] | --all) [--runs <N>] [--json]
+    adventofcode2023 stats --day 2 [--input <path>|-] [--strict] [--json]
+    adventofcode2023 verify [--answers <path>]
+    adventofcode2023 new-day <N>
 
-fn main() {
-    // day_1a();
-    // day_1b();
-    // day_2a();
-    // day_2b();
-    // day_3::day_3a();
-    // day_3::day_3b();
-    // day_4::day_4a();
-    // day_4::day_4b();
-    day_5::day_5b();
+Inputs default to data/day_N and answers to data/answers.toml, under
+$AOC_DATA_DIR or the crate root when unset. --strict rejects day 2 lines with
+trailing text, a color twice in one draw or game ids out of order.";
+
+/// Which days and parts to run and where their input comes from, shared by `run` and `bench`.
+#[derive(Debug)]
+struct Selection {
+    day: Option<u32>,
+    part: Option<Part>,
+    source: InputSource,
+}
+
+enum Command {
+    Run { selection: Selection, format: Format, explain: bool, strict: bool },
+    Bench { selection: Selection, runs: usize, json: bool },
+    Stats { source: InputSource, strictness: Strictness, json: bool },
+    Verify { answers: PathBuf },
+    NewDay { day: u32 },
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let (cmd, rest) = args.split_first().ok_or("missing command")?;
+    match cmd.as_str() {
+        "run" => parse_run_args(rest),
+        "bench" => parse_bench_args(rest),
+        "stats" => parse_stats_args(rest),
+        "verify" => parse_verify_args(rest),
+        "new-day" => match rest {
+            [day] => Ok(Command::NewDay { day: day.parse().map_err(|_| format!("invalid day `{day}`"))? }),
+            _ => Err("new-day takes exactly one day number".to_string()),
+        },
+        _ => Err(format!("unknown command `{cmd}`")),
+    }
+}
+
+fn parse_verify_args(args: &[String]) -> Result<Command, String> {
+    let mut answers = answers::default_path();
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--answers" => answers = PathBuf::from(it.next().ok_or("--answers needs a path")?),
+            other => return Err(format!("unknown argument `{other}`")),
+        }
+    }
+    Ok(Command::Verify { answers })
+}
+
+fn parse_run_args(args: &[String]) -> Result<Command, String> {
+    let mut format = Format::Text;
+    let mut explain = false;
+    let mut strict = false;
+    let selection = parse_selection(args, |arg, it| {
+        match arg {
+            "--explain" => explain = true,
+            "--strict" => strict = true,
+            "--format" => {
+                let v = it.next().ok_or("--format needs a value")?;
+                format = Format::parse(v).ok_or(format!("unknown format `{v}`"))?;
+            }
+            other => return Err(format!("unknown argument `{other}`")),
+        }
+        Ok(())
+    })?;
+    if explain && selection.day != Some(1) {
+        return Err("--explain is only available for day 1".to_string());
+    }
+    // the explanation is plain text on stdout, it would corrupt a json or csv document
+    if explain && format != Format::Text {
+        return Err("--explain cannot be combined with --format json or csv".to_string());
+    }
+    if strict && selection.day != Some(2) {
+        return Err("--strict is only available for day 2".to_string());
+    }
+    Ok(Command::Run { selection, format, explain, strict })
+}
+
+fn parse_bench_args(args: &[String]) -> Result<Command, String> {
+    let mut runs = 10;
+    let mut json = false;
+    let selection = parse_selection(args, |arg, it| {
+        match arg {
+            "--json" => json = true,
+            "--runs" => {
+                let v = it.next().ok_or("--runs needs a value")?;
+                runs = v.parse::<usize>().ok().filter(|r| *r > 0).ok_or(format!("invalid run count `{v}`"))?;
+            }
+            other => return Err(format!("unknown argument `{other}`")),
+        }
+        Ok(())
+    })?;
+    Ok(Command::Bench { selection, runs, json })
+}
+
+fn parse_stats_args(args: &[String]) -> Result<Command, String> {
+    let mut json = false;
+    let mut strictness = Strictness::Lenient;
+    let selection = parse_selection(args, |arg, _| {
+        match arg {
+            "--json" => json = true,
+            "--strict" => strictness = Strictness::Strict,
+            other => return Err(format!("unknown argument `{other}`")),
+        }
+        Ok(())
+    })?;
+    if selection.day != Some(2) || selection.part.is_some() {
+        return Err("stats is only available for day 2, both parts at once".to_string());
+    }
+    Ok(Command::Stats { source: selection.source, strictness, json })
+}
+
+/// Parses the selection flags, handing anything else to `other` along with the remaining args.
+fn parse_selection<'a>(
+    args: &'a [String],
+    mut other: impl FnMut(&str, &mut std::slice::Iter<'a, String>) -> Result<(), String>,
+) -> Result<Selection, String> {
+    let mut day = None;
+    let mut part = None;
+    let mut all = false;
+    let mut source = InputSource::Default;
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--day" => {
+                let v = it.next().ok_or("--day needs a value")?;
+                day = Some(v.parse::<u32>().map_err(|_| format!("invalid day `{v}`"))?);
+            }
+            "--part" => {
+                let v = it.next().ok_or("--part needs a value")?;
+                part = Some(Part::parse(v).ok_or(format!("invalid part `{v}`"))?);
+            }
+            "--input" => {
+                let v = it.next().ok_or("--input needs a path, or - for stdin")?;
+                source = InputSource::from_arg(v);
+            }
+            arg => other(arg, &mut it)?,
+        }
+    }
+
+    match (all, day) {
+        (true, None) if part.is_none() && source == InputSource::Default => Ok(Selection { day: None, part: None, source }),
+        (true, _) => Err("--all cannot be combined with --day, --part or --input".to_string()),
+        (false, None) => Err("either --day or --all is required".to_string()),
+        (false, Some(_)) => Ok(Selection { day, part, source }),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = match parse_args(&args) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match command {
+        Command::Run { selection: Selection { day, part, source }, format, explain, strict } => {
+            let solvers = runner::select(day);
+            if solvers.is_empty() {
+                eprintln!("no solver registered for the requested day");
+                return ExitCode::FAILURE;
+            }
+            // stdin can only be read once, hand the explained text on to the run
+            let source = if explain {
+                let input = match source.load(1) {
+                    Ok(input) => input,
+                    Err(e) => {
+                        eprintln!("cannot read input: {e}");
+                        return ExitCode::FAILURE;
+                    }
+                };
+                for part in runner::parts(part) {
+                    println!("day_1 {part}:\n{}", day_1::calibrator(part).explain(&input));
+                }
+                InputSource::Inline(input)
+            } else if strict {
+                // the registry parses leniently, check the text first and hand it on
+                let input = match source.load(2).map_err(Into::into).and_then(|input| {
+                    day_2::parse_games(&input, Strictness::Strict).map(|_| input)
+                }) {
+                    Ok(input) => input,
+                    Err(e) => {
+                        eprintln!("invalid day_2 input: {e}");
+                        return ExitCode::FAILURE;
+                    }
+                };
+                InputSource::Inline(input)
+            } else {
+                source
+            };
+            let records = runner::run_all(&solvers, &runner::parts(part), &source);
+            print!("{}", output::render(&records, format));
+            if records.iter().any(|r| r.result.is_err()) {
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Bench { selection: Selection { day, part, source }, runs, json } => {
+            let solvers = runner::select(day);
+            if solvers.is_empty() {
+                eprintln!("no solver registered for the requested day");
+                return ExitCode::FAILURE;
+            }
+            let records = runner::bench_all(&solvers, &runner::parts(part), &source, runs);
+            if json {
+                println!("{}", bench::to_json(&records));
+            } else {
+                print!("{}", bench::to_table(&records));
+            }
+            if records.iter().any(|r| r.timings.is_err()) {
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Stats { source, strictness, json } => {
+            let games = match source.load(2).map_err(Into::into).and_then(|input| day_2::parse_games(&input, strictness)) {
+                Ok(games) => games,
+                Err(e) => {
+                    eprintln!("cannot read day_2 games: {e}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            let stats = day_2::stats(&games);
+            if json {
+                println!("{}", stats.to_json());
+            } else {
+                print!("{}", stats.to_table());
+            }
+        }
+        Command::Verify { answers } => {
+            let answers = match Answers::load(&answers) {
+                Ok(a) => a,
+                Err(e) => {
+                    eprintln!("cannot read answers: {e}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            let checks = runner::verify(&answers);
+            print!("{}", runner::verify_table(&checks));
+            if checks.iter().any(|c| c.failed()) {
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::NewDay { day } => {
+            match scaffold::new_day(Path::new(env!("CARGO_MANIFEST_DIR")), &input::data_dir(), day) {
+                Ok(written) => {
+                    for path in written {
+                        println!("wrote {}", path.display());
+                    }
+                }
+                Err(e) => {
+                    eprintln!("cannot create day_{day}: {e}");
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selection(args: &str) -> Result<Selection, String> {
+        let args: Vec<String> = args.split_whitespace().map(str::to_string).collect();
+        parse_selection(&args, |arg, _| Err(format!("unknown argument `{arg}`")))
+    }
+
+    #[test]
+    fn selection_takes_a_day_or_all() {
+        let s = selection("--day 4 --part b --input -").unwrap();
+        assert_eq!((s.day, s.part, s.source), (Some(4), Some(Part::B), InputSource::Stdin));
+
+        let s = selection("--all").unwrap();
+        assert_eq!((s.day, s.part, s.source), (None, None, InputSource::Default));
+
+        assert_eq!(selection("").unwrap_err(), "either --day or --all is required");
+        assert_eq!(selection("--part a").unwrap_err(), "either --day or --all is required");
+    }
+
+    #[test]
+    fn all_conflicts_with_day_part_and_input() {
+        let conflict = "--all cannot be combined with --day, --part or --input";
+        for args in ["--all --day 1", "--day 1 --all", "--all --part a", "--all --input data/day_1", "--all --input -"] {
+            assert_eq!(selection(args).unwrap_err(), conflict, "{args}");
+        }
+    }
+
+    #[test]
+    fn explain_is_text_only() {
+        let args = |s: &str| s.split_whitespace().map(str::to_string).collect::<Vec<_>>();
+        assert!(parse_run_args(&args("--day 1 --explain --format text")).is_ok());
+        for format in ["json", "csv"] {
+            let e = parse_run_args(&args(&format!("--day 1 --explain --format {format}"))).err().unwrap();
+            assert_eq!(e, "--explain cannot be combined with --format json or csv");
+        }
+    }
+
+    #[test]
+    fn strict_is_day_2_only() {
+        let args = |s: &str| s.split_whitespace().map(str::to_string).collect::<Vec<_>>();
+        assert!(matches!(parse_run_args(&args("--day 2 --strict")), Ok(Command::Run { strict: true, .. })));
+        assert_eq!(parse_run_args(&args("--day 1 --strict")).err().unwrap(), "--strict is only available for day 2");
+        assert!(matches!(
+            parse_stats_args(&args("--day 2 --strict")),
+            Ok(Command::Stats { strictness: Strictness::Strict, .. })
+        ));
+    }
+
+    #[test]
+    fn selection_rejects_bad_values() {
+        assert_eq!(selection("--day x").unwrap_err(), "invalid day `x`");
+        assert_eq!(selection("--day 1 --part c").unwrap_err(), "invalid part `c`");
+        assert_eq!(selection("--day 1 --input").unwrap_err(), "--input needs a path, or - for stdin");
+        assert_eq!(selection("--day 1 --runs 3").unwrap_err(), "unknown argument `--runs`");
+    }
 }
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use crate::error::Result;
+use crate::runner::Part;
+use crate::solution::Answer;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Option<Format> {
+        match s {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// The outcome of running one part, `elapsed` covering both parse and solve.
+pub struct RunRecord {
+    pub day: u32,
+    pub part: Part,
+    pub result: Result<Answer>,
+    pub elapsed: Duration,
+}
+
+pub fn render(records: &[RunRecord], format: Format) -> String {
+    match format {
+        Format::Text => to_text(records),
+        Format::Json => to_json(records),
+        Format::Csv => to_csv(records),
+    }
+}
+
+fn to_text(records: &[RunRecord]) -> String {
+    records.iter().map(|r| match &r.result {
+        Ok(answer) => format!("day_{} {}: {answer}\n", r.day, r.part),
+        Err(e) => format!("day_{} {}: FAILED ({e})\n", r.day, r.part),
+    }).collect()
+}
+
+pub(crate) fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A JSON array with one object per record. Answers are always strings so a `BigInt` keeps
+/// every digit, a failed part has `"answer":null` and an `"error"` message instead.
+fn to_json(records: &[RunRecord]) -> String {
+    let rows: Vec<String> = records.iter().map(|r| {
+        let outcome = match &r.result {
+            Ok(answer) => format!(r#""answer":{}"#, json_string(&answer.to_string())),
+            Err(e) => format!(r#""answer":null,"error":{}"#, json_string(&e.to_string())),
+        };
+        format!(
+            r#"  {{"day":{},"part":"{}",{outcome},"elapsed_us":{}}}"#,
+            r.day, r.part, r.elapsed.as_micros()
+        )
+    }).collect();
+    format!("[\n{}\n]\n", rows.join(",\n"))
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn to_csv(records: &[RunRecord]) -> String {
+    let mut out = "day,part,answer,elapsed_us,error\n".to_string();
+    for r in records {
+        let (answer, error) = match &r.result {
+            Ok(answer) => (answer.to_string(), String::new()),
+            Err(e) => (String::new(), e.to_string()),
+        };
+        out.push_str(&format!(
+            "{},{},{},{},{}\n",
+            r.day, r.part, csv_field(&answer), r.elapsed.as_micros(), csv_field(&error)
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    fn records() -> Vec<RunRecord> {
+        vec![
+            RunRecord { day: 5, part: Part::B, result: Ok(Answer::U64(46)), elapsed: Duration::from_micros(12) },
+            RunRecord { day: 4, part: Part::A, result: Err(Error::invalid("bad \"card\", line").in_day(4)), elapsed: Duration::ZERO },
+        ]
+    }
+
+    #[test]
+    fn json_quotes_answers_and_errors() {
+        assert_eq!(render(&records(), Format::Json), "[
+  {\"day\":5,\"part\":\"b\",\"answer\":\"46\",\"elapsed_us\":12},
+  {\"day\":4,\"part\":\"a\",\"answer\":null,\"error\":\"day_4: bad \\\"card\\\", line\",\"elapsed_us\":0}
+]
+");
+    }
+
+    #[test]
+    fn csv_escapes_fields() {
+        assert_eq!(render(&records(), Format::Csv), "day,part,answer,elapsed_us,error
+5,b,46,12,
+4,a,,0,\"day_4: bad \"\"card\"\", line\"
+");
+    }
+}
@@ -0,0 +1,237 @@
+use std::fmt;
+use std::io;
+use std::panic;
+use std::time::{Duration, Instant};
+
+use crate::answers::Answers;
+use crate::bench::{self, Record, Timings};
+use crate::error::{Error, Result};
+use crate::input::InputSource;
+use crate::output::RunRecord;
+use crate::solution::{Answer, Solution};
+use crate::{day_1, day_2, day_3, day_4, day_5};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Part {
+    A,
+    B,
+}
+
+impl Part {
+    pub fn parse(s: &str) -> Option<Part> {
+        match s {
+            "a" | "A" => Some(Part::A),
+            "b" | "B" => Some(Part::B),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::A => f.pad("a"),
+            Part::B => f.pad("b"),
+        }
+    }
+}
+
+/// Parses `input` with `S` and answers the requested part.
+pub fn solve<S: Solution>(input: &str, part: Part) -> Result<Answer> {
+    let model = S::parse(input)?;
+    match part {
+        Part::A => S::part_a(&model),
+        Part::B => S::part_b(&model),
+    }
+}
+
+pub struct Solver {
+    pub day: u32,
+    pub solve: fn(&str, Part) -> Result<Answer>,
+    pub bench: fn(&str, Part, usize) -> Result<Timings>,
+}
+
+impl Solver {
+    pub const fn of<S: Solution>(day: u32) -> Solver {
+        Solver { day, solve: solve::<S>, bench: bench::time::<S> }
+    }
+}
+
+pub static SOLVERS: &[Solver] = &[
+    Solver::of::<day_1::Day1>(1),
+    Solver::of::<day_2::Day2>(2),
+    Solver::of::<day_3::Day3>(3),
+    Solver::of::<day_4::Day4>(4),
+    Solver::of::<day_5::Day5>(5),
+];
+
+/// Picks the registered solvers for `day`, `None` meaning all of them.
+pub fn select(day: Option<u32>) -> Vec<&'static Solver> {
+    SOLVERS.iter()
+        .filter(|s| day.is_none_or(|d| s.day == d))
+        .collect()
+}
+
+/// `Some(part)` runs just that part, `None` runs both.
+pub fn parts(part: Option<Part>) -> Vec<Part> {
+    match part {
+        Some(p) => vec![p],
+        None => vec![Part::A, Part::B],
+    }
+}
+
+/// Runs one part, turning a panic into an error: a bug in one solver should not stop the others.
+pub fn run_one(solver: &Solver, part: Part, input: &str) -> Result<Answer> {
+    panic::catch_unwind(|| (solver.solve)(input, part))
+        .unwrap_or_else(|_| Err(Error::invalid("solver panicked")))
+        .map_err(|e| e.in_day(solver.day))
+}
+
+/// Runs each selected part, timing parse and solve together. Failures become records too.
+pub fn run_all(solvers: &[&Solver], parts: &[Part], source: &InputSource) -> Vec<RunRecord> {
+    let mut records = vec![];
+    for solver in solvers {
+        // load once per day, stdin can only be read once
+        let input = match source.load(solver.day) {
+            Ok(input) => input,
+            Err(e) => {
+                for part in parts {
+                    let result = Err(Error::from(io::Error::new(e.kind(), e.to_string())).in_day(solver.day));
+                    records.push(RunRecord { day: solver.day, part: *part, result, elapsed: Duration::ZERO });
+                }
+                continue;
+            }
+        };
+        for part in parts {
+            let start = Instant::now();
+            let result = run_one(solver, *part, &input);
+            records.push(RunRecord { day: solver.day, part: *part, result, elapsed: start.elapsed() });
+        }
+    }
+    records
+}
+
+/// Benchmarks each selected part `runs` times, one record per part whether or not it completed.
+pub fn bench_all(solvers: &[&Solver], parts: &[Part], source: &InputSource, runs: usize) -> Vec<Record> {
+    let mut records = vec![];
+    for solver in solvers {
+        let input = match source.load(solver.day) {
+            Ok(input) => input,
+            Err(e) => {
+                for part in parts {
+                    let timings = Err(Error::from(io::Error::new(e.kind(), e.to_string())).in_day(solver.day));
+                    records.push(Record { day: solver.day, part: *part, runs, timings });
+                }
+                continue;
+            }
+        };
+        for part in parts {
+            let timings = panic::catch_unwind(|| (solver.bench)(&input, *part, runs))
+                .unwrap_or_else(|_| Err(Error::invalid("solver panicked")))
+                .map_err(|e| e.in_day(solver.day));
+            records.push(Record { day: solver.day, part: *part, runs, timings });
+        }
+    }
+    records
+}
+
+/// How a part's answer compares with the recorded one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    /// The input could not be read or the solver failed.
+    Error,
+    /// No answer recorded to compare with.
+    Missing,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Status::Pass => "PASS",
+            Status::Fail => "FAIL",
+            Status::Error => "ERROR",
+            Status::Missing => "MISSING",
+        })
+    }
+}
+
+/// One part checked against its recorded answer. `actual` is the answer, or the error when
+/// `status` is [`Status::Error`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Check {
+    pub day: u32,
+    pub part: Part,
+    pub expected: Option<String>,
+    pub actual: String,
+    pub status: Status,
+}
+
+impl Check {
+    pub fn failed(&self) -> bool {
+        matches!(self.status, Status::Error | Status::Fail)
+    }
+}
+
+fn check(day: u32, part: Part, expected: Option<&str>, actual: std::result::Result<String, String>) -> Check {
+    let status = match (&actual, expected) {
+        (Err(_), _) => Status::Error,
+        (Ok(_), None) => Status::Missing,
+        (Ok(a), Some(exp)) if a == exp => Status::Pass,
+        (Ok(_), Some(_)) => Status::Fail,
+    };
+    let actual = actual.unwrap_or_else(|e| e);
+    Check { day, part, expected: expected.map(str::to_string), actual, status }
+}
+
+/// Runs every registered solver against its default input and compares with the recorded
+/// answers, one check per part. Parts without a recorded answer are [`Status::Missing`],
+/// which [`Check::failed`] does not count.
+pub fn verify(answers: &Answers) -> Vec<Check> {
+    let mut checks = vec![];
+    for solver in SOLVERS.iter() {
+        let input = InputSource::Default.load(solver.day).map_err(|e| Error::from(e).in_day(solver.day));
+        for part in [Part::A, Part::B] {
+            let actual = input.as_ref()
+                .map_err(|e| e.to_string())
+                .and_then(|input| run_one(solver, part, input).map(|a| a.to_string()).map_err(|e| e.to_string()));
+            checks.push(check(solver.day, part, answers.get(solver.day, part), actual));
+        }
+    }
+    checks
+}
+
+pub fn verify_table(checks: &[Check]) -> String {
+    let mut out = format!("{:<4} {:<4} {:<20} {:<20} result\n", "day", "part", "expected", "actual");
+    for c in checks {
+        out.push_str(&format!(
+            "{:<4} {:<4} {:<20} {:<20} {}\n",
+            c.day, c.part, c.expected.as_deref().unwrap_or("-"), c.actual, c.status
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_compare_answers_as_text() {
+        let pass = check(1, Part::A, Some("142"), Ok("142".to_string()));
+        let fail = check(1, Part::B, Some("281"), Ok("280".to_string()));
+        let missing = check(2, Part::A, None, Ok("8".to_string()));
+        let error = check(2, Part::B, Some("2286"), Err("day_2: no input".to_string()));
+        let statuses: Vec<Status> = [&pass, &fail, &missing, &error].iter().map(|c| c.status).collect();
+        assert_eq!(statuses, [Status::Pass, Status::Fail, Status::Missing, Status::Error]);
+        assert_eq!([&pass, &fail, &missing, &error].iter().filter(|c| c.failed()).count(), 2);
+
+        assert_eq!(verify_table(&[pass, missing, error]), "\
+day  part expected             actual               result
+1    a    142                  142                  PASS
+2    a    -                    8                    MISSING
+2    b    2286                 day_2: no input      ERROR
+");
+    }
+}
@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+
+const TEMPLATE: &str = include_str!("../templates/day.rs");
+
+/// Creates `src/day_N.rs` under `root` from the template, registers it in `src/lib.rs` and
+/// `src/runner.rs`, and creates an empty `day_N` input in `data_dir`. Returns what was written.
+pub fn new_day(root: &Path, data_dir: &Path, day: u32) -> Result<Vec<PathBuf>> {
+    let module = root.join("src").join(format!("day_{day}.rs"));
+    if module.exists() {
+        return Err(Error::invalid(format!("{} already exists", module.display())));
+    }
+
+    let lib = root.join("src").join("lib.rs");
+    let lib_text = register_module(&fs::read_to_string(&lib)?, day)?;
+    let runner = root.join("src").join("runner.rs");
+    let runner_text = register_solver(&fs::read_to_string(&runner)?, day)?;
+
+    fs::write(&module, TEMPLATE.replace("__DAY__", &day.to_string()))?;
+    fs::write(&lib, lib_text)?;
+    fs::write(&runner, runner_text)?;
+    let mut written = vec![module, lib, runner];
+
+    let input = data_dir.join(format!("day_{day}"));
+    if !input.exists() {
+        fs::create_dir_all(data_dir)?;
+        fs::write(&input, "")?;
+        written.push(input);
+    }
+    Ok(written)
+}
+
+/// Inserts `text` after the last line starting with `prefix`.
+fn insert_after_last(source: &str, prefix: &str, text: &str) -> Result<String> {
+    let mut lines: Vec<&str> = source.lines().collect();
+    let last = lines.iter().rposition(|l| l.trim_start().starts_with(prefix))
+        .ok_or_else(|| Error::invalid(format!("no line starting with `{prefix}` to register after")))?;
+    lines.insert(last + 1, text);
+    Ok(lines.join("\n") + "\n")
+}
+
+fn register_module(lib: &str, day: u32) -> Result<String> {
+    insert_after_last(lib, "pub mod day_", &format!("pub mod day_{day};"))
+}
+
+fn register_solver(runner: &str, day: u32) -> Result<String> {
+    let import = runner.lines().find(|l| l.starts_with("use crate::{day_"))
+        .ok_or_else(|| Error::invalid("no `use crate::{day_..}` import in runner.rs"))?;
+    let mut days: Vec<u32> = import.trim_start_matches("use crate::{").trim_end_matches("};")
+        .split(", ")
+        .filter_map(|m| m.trim_start_matches("day_").parse().ok())
+        .collect();
+    days.push(day);
+    days.sort();
+    let modules: Vec<String> = days.iter().map(|d| format!("day_{d}")).collect();
+    let with_use = runner.replacen(import, &format!("use crate::{{{}}};", modules.join(", ")), 1);
+
+    insert_after_last(&with_use, "Solver::of::<day_", &format!("    Solver::of::<day_{day}::Day{day}>({day}),"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_module_and_solver() {
+        let lib = "pub mod bench;\npub mod day_1;\npub mod day_2;\npub mod error;\n";
+        assert_eq!(register_module(lib, 3).unwrap(), "pub mod bench;\npub mod day_1;\npub mod day_2;\npub mod day_3;\npub mod error;\n");
+
+        let runner = "use crate::{day_1, day_2};\n\npub static SOLVERS: &[Solver] = &[\n    Solver::of::<day_1::Day1>(1),\n    Solver::of::<day_2::Day2>(2),\n];\n";
+        assert_eq!(
+            register_solver(runner, 3).unwrap(),
+            "use crate::{day_1, day_2, day_3};\n\npub static SOLVERS: &[Solver] = &[\n    Solver::of::<day_1::Day1>(1),\n    Solver::of::<day_2::Day2>(2),\n    Solver::of::<day_3::Day3>(3),\n];\n"
+        );
+    }
+
+    #[test]
+    fn template_fills_in_the_day() {
+        let module = TEMPLATE.replace("__DAY__", "12");
+        assert!(module.contains("pub struct Day12;"));
+        assert!(module.contains("pub fn day_12a("));
+        assert!(!module.contains("__DAY__"));
+    }
+}
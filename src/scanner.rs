@@ -0,0 +1,146 @@
+use crate::automaton::{Automaton, Match};
+
+/// Finds the first and last occurrence of any of a set of tokens in one forward pass.
+///
+/// "First" is the occurrence starting earliest, "last" the one ending latest, overlaps
+/// included: with `one` and `eight` as tokens, "oneight" starts with `one` and ends with
+/// `eight`. Text is never reversed, so the tokens need no reversed copies either.
+#[derive(Clone, Debug)]
+pub struct Scanner<V> {
+    automaton: Automaton<V>,
+    /// Length of the longest token, bounding how far back an occurrence can start.
+    longest: usize,
+}
+
+impl<V: Copy> Scanner<V> {
+    pub fn new<T: AsRef<[u8]>>(tokens: impl IntoIterator<Item = (T, V)>) -> Scanner<V> {
+        let tokens: Vec<(T, V)> = tokens.into_iter().collect();
+        let longest = tokens.iter().map(|(t, _)| t.as_ref().len()).max().unwrap_or(0);
+        Scanner { automaton: Automaton::new(tokens), longest }
+    }
+
+    /// A scan to feed byte by byte, for text that arrives in pieces.
+    pub fn start(&self) -> Scan<'_, V> {
+        Scan { scanner: self, state: Automaton::<V>::START, offset: 0, first: None, last: None }
+    }
+
+    /// The earliest starting occurrence, stopping as soon as no later one can start before it.
+    pub fn first(&self, text: &str) -> Option<Match<V>> {
+        let mut scan = self.start();
+        for &b in text.as_bytes() {
+            scan.push(b);
+            if scan.first.is_some_and(|f| scan.offset >= f.start + self.longest) {
+                break;
+            }
+        }
+        scan.first
+    }
+
+    /// The latest ending occurrence, the longer one when several end together.
+    pub fn last(&self, text: &str) -> Option<Match<V>> {
+        self.first_last(text).map(|(_, last)| last)
+    }
+
+    /// Both ends at once, `None` when no token occurs at all.
+    pub fn first_last(&self, text: &str) -> Option<(Match<V>, Match<V>)> {
+        let mut scan = self.start();
+        scan.extend(text.as_bytes());
+        Some((scan.first?, scan.last?))
+    }
+}
+
+/// An in-progress [`Scanner`] pass. Offsets count bytes pushed since the start or the last
+/// [`Scan::reset`], and pushing never allocates.
+#[derive(Clone, Debug)]
+pub struct Scan<'a, V> {
+    scanner: &'a Scanner<V>,
+    state: u32,
+    offset: usize,
+    first: Option<Match<V>>,
+    last: Option<Match<V>>,
+}
+
+impl<V: Copy> Scan<'_, V> {
+    pub fn push(&mut self, byte: u8) {
+        let automaton = &self.scanner.automaton;
+        self.state = automaton.step(self.state, byte);
+        self.offset += 1;
+        // outputs are longest first, so the first one is also the earliest starting
+        if let Some(&(len, value)) = automaton.outputs(self.state).first() {
+            let found = Match { start: self.offset - len, end: self.offset, value };
+            if self.first.is_none_or(|f| found.start < f.start) {
+                self.first = Some(found);
+            }
+            self.last = Some(found);
+        }
+    }
+
+    pub fn extend(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.push(b);
+        }
+    }
+
+    /// Bytes pushed so far.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn first(&self) -> Option<Match<V>> {
+        self.first
+    }
+
+    pub fn last(&self) -> Option<Match<V>> {
+        self.last
+    }
+
+    /// Starts over, as if nothing had been pushed.
+    pub fn reset(&mut self) {
+        self.state = Automaton::<V>::START;
+        self.offset = 0;
+        self.first = None;
+        self.last = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Span = (usize, usize, char);
+
+    fn spans(found: Option<(Match<char>, Match<char>)>) -> Option<(Span, Span)> {
+        found.map(|(f, l)| ((f.start, f.end, f.value), (l.start, l.end, l.value)))
+    }
+
+    #[test]
+    fn finds_both_ends_without_reversing() {
+        let scanner = Scanner::new([("ab", 'x'), ("bab", 'y'), ("c", 'z')]);
+        assert_eq!(spans(scanner.first_last("cbab")), Some(((0, 1, 'z'), (1, 4, 'y'))));
+        assert_eq!(spans(scanner.first_last("babab")), Some(((0, 3, 'y'), (2, 5, 'y'))));
+        assert_eq!(spans(scanner.first_last("aab")), Some(((1, 3, 'x'), (1, 3, 'x'))));
+        assert_eq!(scanner.first_last("ba"), None);
+    }
+
+    #[test]
+    fn first_prefers_the_earlier_start_over_the_earlier_end() {
+        let scanner = Scanner::new([("abcd", 1), ("bc", 2)]);
+        assert_eq!(scanner.first("abcd").map(|m| m.value), Some(1));
+        assert_eq!(scanner.first("abcx").map(|m| m.value), Some(2));
+        assert_eq!(scanner.last("abcd").map(|m| m.value), Some(1));
+    }
+
+    #[test]
+    fn scan_carries_over_between_pieces() {
+        let scanner = Scanner::new([("one", 1), ("eight", 8)]);
+        let mut scan = scanner.start();
+        for piece in ["on", "eig", "h", "t"] {
+            scan.extend(piece.as_bytes());
+        }
+        assert_eq!((scan.first().unwrap().value, scan.last().unwrap().value), (1, 8));
+
+        scan.reset();
+        scan.extend(b"ne");
+        assert_eq!((scan.offset(), scan.first()), (2, None));
+    }
+}
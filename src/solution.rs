@@ -0,0 +1,58 @@
+use std::fmt;
+
+use num::BigInt;
+
+use crate::error::Result;
+
+/// The result of one puzzle part, wide enough for every day so far.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    U32(u32),
+    U64(u64),
+    BigInt(BigInt),
+    String(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::U32(v) => write!(f, "{v}"),
+            Answer::U64(v) => write!(f, "{v}"),
+            Answer::BigInt(v) => write!(f, "{v}"),
+            Answer::String(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(v: u32) -> Self {
+        Answer::U32(v)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(v: u64) -> Self {
+        Answer::U64(v)
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(v: BigInt) -> Self {
+        Answer::BigInt(v)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(v: String) -> Self {
+        Answer::String(v)
+    }
+}
+
+/// A day's puzzle: parse the input once, then answer both parts from the model.
+pub trait Solution {
+    type Model;
+
+    fn parse(input: &str) -> Result<Self::Model>;
+    fn part_a(model: &Self::Model) -> Result<Answer>;
+    fn part_b(model: &Self::Model) -> Result<Answer>;
+}
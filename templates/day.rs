@@ -0,0 +1,61 @@
+use nom::character::complete::{digit1, space1};
+use nom::combinator::map_res;
+use nom::multi::separated_list1;
+
+use crate::error::{parse_line, Error, IResult, Result};
+use crate::solution::{Answer, Solution};
+
+fn parse_number(input: &str) -> IResult<'_, u64> {
+    map_res(digit1, str::parse)(input)
+}
+
+fn parse_numbers(input: &str) -> IResult<'_, Vec<u64>> {
+    separated_list1(space1, parse_number)(input)
+}
+
+pub struct Day__DAY__;
+
+impl Solution for Day__DAY__ {
+    type Model = Vec<Vec<u64>>;
+
+    fn parse(input: &str) -> Result<Self::Model> {
+        input.lines().enumerate().map(|(i, line)| parse_line(i, line, parse_numbers)).collect()
+    }
+
+    fn part_a(lines: &Self::Model) -> Result<Answer> {
+        Ok(day___DAY__a(lines)?.into())
+    }
+
+    fn part_b(lines: &Self::Model) -> Result<Answer> {
+        Ok(day___DAY__b(lines)?.into())
+    }
+}
+
+pub fn day___DAY__a(_lines: &[Vec<u64>]) -> Result<u64> {
+    Err(Error::invalid("part a is not solved yet"))
+}
+
+pub fn day___DAY__b(_lines: &[Vec<u64>]) -> Result<u64> {
+    Err(Error::invalid("part b is not solved yet"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    #[ignore = "paste the puzzle example and its answer"]
+    fn part_a_example() {
+        let lines = Day__DAY__::parse(EXAMPLE).unwrap();
+        assert_eq!(Day__DAY__::part_a(&lines).unwrap(), Answer::U64(0));
+    }
+
+    #[test]
+    #[ignore = "paste the puzzle example and its answer"]
+    fn part_b_example() {
+        let lines = Day__DAY__::parse(EXAMPLE).unwrap();
+        assert_eq!(Day__DAY__::part_b(&lines).unwrap(), Answer::U64(0));
+    }
+}
@@ -0,0 +1,28 @@
+use adventofcode2023::day_2::{parse_game, Day2};
+use adventofcode2023::input::InputSource;
+use adventofcode2023::runner::{self, Part};
+use adventofcode2023::solution::{Answer, Solution};
+
+const DAY_2_EXAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+#[test]
+fn parsers_and_models_are_public() {
+    let (_, game) = parse_game("Game 7: 1 red, 2 green; 3 blue").unwrap();
+    assert_eq!(game.id, 7);
+    assert_eq!(game.power(), 6);
+
+    let games = Day2::parse(DAY_2_EXAMPLE).unwrap();
+    assert_eq!(Day2::part_b(&games).unwrap(), Answer::U32(2286));
+}
+
+#[test]
+fn registry_runs_inline_input() {
+    let solvers = runner::select(Some(2));
+    let records = runner::run_all(&solvers, &[Part::A], &InputSource::Inline(DAY_2_EXAMPLE.to_string()));
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].result.as_ref().unwrap(), &Answer::U32(8));
+}